@@ -1,7 +1,15 @@
+import Foundation
+
 public typealias Vocab = [String: UInt32]
 
 public typealias Merges = [(String, String)]
 
+public typealias Offsets = (start: Int, end: Int)
+
+/// The unit of the offsets of an ``Encoding``: UTF-8 bytes, unicode scalars or UTF-16 code
+/// units.
+public typealias OffsetType = RustOffsetType
+
 /// `InputSequence` represent all the different kinds of sequence that can be used as
 /// input of a Tokenizer. Globally, any sequence can be either a string or a list of strings,
 /// according to the operating mode of the tokenizer: raw text vs pre-tokenized.
@@ -33,24 +41,174 @@ public enum InputSequence: ExpressibleByStringLiteral, CustomStringConvertible {
     }
 }
 
+public typealias PaddingDirection = RustPaddingDirection
+
+/// The parameters used to pad the encodings of a ``Tokenizer``.
+public struct PaddingParams {
+    /// The length to pad to.
+    public enum Strategy {
+        /// Pad to the longest sequence of the batch.
+        case batchLongest
+        /// Pad to the given length.
+        case fixed(Int)
+    }
+
+    public var strategy: Strategy
+    public var direction: PaddingDirection
+    /// If set, the padded length is rounded up to a multiple of this value.
+    public var padToMultipleOf: Int?
+    public var padId: UInt32
+    public var padTypeId: UInt32
+    public var padToken: String
+
+    public init(
+        strategy: Strategy = .batchLongest, direction: PaddingDirection = .right,
+        padToMultipleOf: Int? = nil, padId: UInt32 = 0, padTypeId: UInt32 = 0,
+        padToken: String = "[PAD]"
+    ) {
+        self.strategy = strategy
+        self.direction = direction
+        self.padToMultipleOf = padToMultipleOf
+        self.padId = padId
+        self.padTypeId = padTypeId
+        self.padToken = padToken
+    }
+
+    init(_ params: RustPaddingParams) {
+        switch params.strategy {
+        case .batchLongest:
+            self.strategy = .batchLongest
+        case .fixed(let size):
+            self.strategy = .fixed(Int(size))
+        }
+        self.direction = params.direction
+        self.padToMultipleOf = params.padToMultipleOf.map { Int($0) }
+        self.padId = params.padId
+        self.padTypeId = params.padTypeId
+        self.padToken = params.padToken
+    }
+
+    func toRustPaddingParams() -> RustPaddingParams {
+        let strategy: RustPaddingStrategy
+        switch self.strategy {
+        case .batchLongest:
+            strategy = .batchLongest
+        case .fixed(let size):
+            strategy = .fixed(size: UInt64(size))
+        }
+        return RustPaddingParams(
+            strategy: strategy, direction: self.direction,
+            padToMultipleOf: self.padToMultipleOf.map { UInt64($0) }, padId: self.padId,
+            padTypeId: self.padTypeId, padToken: self.padToken)
+    }
+}
+
+public typealias TruncationDirection = RustTruncationDirection
+
+public typealias TruncationStrategy = RustTruncationStrategy
+
+/// The parameters used to truncate the encodings of a ``Tokenizer``.
+public struct TruncationParams {
+    /// The maximum length of the encodings, special tokens included.
+    public var maxLength: Int
+    /// The number of tokens each overflowing encoding shares with the previous one.
+    public var stride: Int
+    public var strategy: TruncationStrategy
+    public var direction: TruncationDirection
+
+    public init(
+        maxLength: Int, stride: Int = 0, strategy: TruncationStrategy = .longestFirst,
+        direction: TruncationDirection = .right
+    ) {
+        self.maxLength = maxLength
+        self.stride = stride
+        self.strategy = strategy
+        self.direction = direction
+    }
+
+    init(_ params: RustTruncationParams) {
+        self.maxLength = Int(params.maxLength)
+        self.stride = Int(params.stride)
+        self.strategy = params.strategy
+        self.direction = params.direction
+    }
+
+    func toRustTruncationParams() -> RustTruncationParams {
+        RustTruncationParams(
+            maxLength: UInt64(self.maxLength), stride: UInt64(self.stride),
+            strategy: self.strategy, direction: self.direction)
+    }
+}
+
+public typealias BufferDType = RustBufferDType
+
+/// Tensors of the same shape, stacked in a single row-major buffer.
+public struct BatchBuffer {
+    /// The name of each tensor, along the first dimension.
+    public let names: [String]
+    /// `[names.count, batch size, sequence length]`
+    public let shape: [Int]
+    public let dtype: BufferDType
+    /// The elements, in native byte order. They are the memory of the Rust buffer, which is
+    /// kept alive as long as this `Data` or a copy of it is, e.g. to back an `MLMultiArray`.
+    public let data: Data
+
+    init(_ buffer: RustBatchBuffer) {
+        self.names = buffer.getNames()
+        self.shape = buffer.getShape().map { Int($0) }
+        self.dtype = buffer.getDtype()
+
+        let count = Int(buffer.getDataLength())
+        if count == 0 {
+            self.data = Data()
+        } else {
+            let address = UnsafeMutableRawPointer(bitPattern: UInt(buffer.getDataAddress()))!
+            self.data = Data(
+                bytesNoCopy: address, count: count,
+                deallocator: .custom { _, _ in withExtendedLifetime(buffer) {} })
+        }
+    }
+
+    /// The bytes of the tensor with the given name, if any, sharing the memory of ``data``.
+    public func data(named name: String) -> Data? {
+        guard let index = self.names.firstIndex(of: name) else { return nil }
+        let size = self.data.count / max(self.names.count, 1)
+        return self.data[index * size..<(index + 1) * size]
+    }
+}
+
 /// A `Tokenizer` works as a pipeline. It processes some raw text as input
 /// and outputs an `Encoding`.
 public class Tokenizer {
     let tokenizer: RustTokenizer
 
-    public var model: BPE {
+    public var model: Model {
         get {
-            return BPE(model: self.tokenizer.getModel())
+            return Model.wrap(self.tokenizer.getModel())
         }
         set(model) {
             self.tokenizer.setModel(model: model.model)
         }
     }
 
-    public var preTokenizer: Whitespace? {
+    public var normalizer: Normalizer? {
+        get {
+            guard let unwrapped = self.tokenizer.getNormalizer() else { return nil }
+            return Normalizer.wrap(unwrapped)
+        }
+        set(value) {
+            if let normalizer = value {
+                self.tokenizer.setNormalizer(normalizer: normalizer.normalizer)
+            } else {
+                fatalError("You cannot set normalizer to nil")
+            }
+        }
+    }
+
+    public var preTokenizer: PreTokenizer? {
         get {
             guard let unwrapped = self.tokenizer.getPreTokenizer() else { return nil }
-            return Whitespace(preTokenizer: unwrapped)
+            return PreTokenizer.wrap(unwrapped)
         }
         set(value) {
             if let tok = value {
@@ -61,7 +219,75 @@ public class Tokenizer {
         }
     }
 
-    public init(model: BPE) {
+    public var postProcessor: PostProcessor? {
+        get {
+            guard let unwrapped = self.tokenizer.getPostProcessor() else { return nil }
+            return PostProcessor.wrap(unwrapped)
+        }
+        set(value) {
+            if let processor = value {
+                self.tokenizer.setPostProcessor(postProcessor: processor.postProcessor)
+            } else {
+                fatalError("You cannot set postProcessor to nil")
+            }
+        }
+    }
+
+    public var decoder: Decoder? {
+        get {
+            guard let unwrapped = self.tokenizer.getDecoder() else { return nil }
+            return Decoder.wrap(unwrapped)
+        }
+        set(value) {
+            if let decoder = value {
+                self.tokenizer.setDecoder(decoder: decoder.decoder)
+            } else {
+                fatalError("You cannot set decoder to nil")
+            }
+        }
+    }
+
+    /// The padding applied to the encodings, or `nil` to disable it.
+    ///
+    /// Without a fixed length, a single encoding is only padded up to a multiple of
+    /// `padToMultipleOf`, if any, whereas a batch is padded to its longest encoding.
+    public var padding: PaddingParams? {
+        get {
+            self.tokenizer.getPadding().map { PaddingParams($0) }
+        }
+        set(value) {
+            if let params = value {
+                self.tokenizer.withPadding(params: params.toRustPaddingParams())
+            } else {
+                self.tokenizer.noPadding()
+            }
+        }
+    }
+
+    /// The truncation applied to the encodings, if any. See ``setTruncation(_:)``.
+    public var truncation: TruncationParams? {
+        self.tokenizer.getTruncation().map { TruncationParams($0) }
+    }
+
+    /// Truncate the encodings with the given parameters, or disable the truncation with `nil`.
+    ///
+    /// The tokens which don't fit are kept in the ``Encoding/overflowing`` encodings, each of
+    /// them starting with the last `stride` tokens of the previous one.
+    ///
+    /// - Parameters:
+    ///     - params:
+    ///         The truncation parameters. It fails if `maxLength` doesn't leave room for more
+    ///         than `stride` tokens besides the special tokens. Encoding a pair fails as well
+    ///         when one of its sequences would be truncated to `stride` tokens or less.
+    public func setTruncation(_ params: TruncationParams?) throws {
+        if let params = params {
+            try self.tokenizer.withTruncation(params: params.toRustTruncationParams())
+        } else {
+            self.tokenizer.noTruncation()
+        }
+    }
+
+    public init(model: Model) {
         self.tokenizer = RustTokenizer(model: model.model)
     }
 
@@ -125,15 +351,154 @@ public class Tokenizer {
     ///   - addSpecialTokens:
     ///         Whether to add the special tokens
     ///
+    ///   - offsetType:
+    ///         The unit of the offsets of the encoding. Use `.utf16` to get offsets compatible
+    ///         with `NSString` and `NSRange`.
+    ///
     /// - Returns:
     ///     The encoded result
     ///
     public func encode(
-        _ input: InputSequence, pair: InputSequence? = nil, addSpecialTokens: Bool = true
+        _ input: InputSequence, pair: InputSequence? = nil, addSpecialTokens: Bool = true,
+        offsetType: OffsetType = .char
     ) throws -> Encoding {
         let encoding = try self.tokenizer.encode(
             input: input.toRustInputSequence(),
             pair: pair?.toRustInputSequence(),
+            addSpecialTokens: addSpecialTokens,
+            offsetType: offsetType)
+        return Encoding(encoding)
+    }
+
+    /// Encode the given batch of inputs in parallel. This method accepts raw text sequences
+    /// as well as already pre-tokenized sequences.
+    ///
+    /// ```
+    /// encodeBatch(["A single sequence", "Another sequence"])
+    /// encodeBatch([.preTokenized([ "A", "pre", "tokenized", "sequence" ])])
+    /// ```
+    ///
+    /// - Parameters:
+    ///
+    ///   - inputs:
+    ///         A list of single sequences to encode.
+    ///
+    ///   - addSpecialTokens:
+    ///         Whether to add the special tokens
+    ///
+    /// - Returns:
+    ///     The encoded batch
+    ///
+    public func encodeBatch(_ inputs: [InputSequence], addSpecialTokens: Bool = true) throws
+        -> [Encoding]
+    {
+        let inputs = inputs.map { RustEncodeInput.single(input: $0.toRustInputSequence()) }
+        return try self.tokenizer.encodeBatch(inputs: inputs, addSpecialTokens: addSpecialTokens)
+            .map { Encoding($0) }
+    }
+
+    /// Encode the given batch of pairs in parallel. This method accepts raw text sequences
+    /// as well as already pre-tokenized sequences.
+    ///
+    /// ```
+    /// encodeBatch(pairs: [("A sequence", "And its pair"), ("Another", "pair")])
+    /// ```
+    ///
+    /// - Parameters:
+    ///
+    ///   - pairs:
+    ///         A list of pairs of sequences to encode.
+    ///
+    ///   - addSpecialTokens:
+    ///         Whether to add the special tokens
+    ///
+    /// - Returns:
+    ///     The encoded batch
+    ///
+    public func encodeBatch(
+        pairs: [(InputSequence, InputSequence)], addSpecialTokens: Bool = true
+    ) throws -> [Encoding] {
+        let inputs = pairs.map {
+            RustEncodeInput.dual(
+                input: $0.0.toRustInputSequence(), pair: $0.1.toRustInputSequence())
+        }
+        return try self.tokenizer.encodeBatch(inputs: inputs, addSpecialTokens: addSpecialTokens)
+            .map { Encoding($0) }
+    }
+
+    /// Encode the given batch of inputs into a ``BatchBuffer`` holding the `input_ids`,
+    /// `attention_mask`, `token_type_ids` and optionally `position_ids` of the batch, laid out
+    /// like the inputs of a Core ML or ONNX Runtime model.
+    ///
+    /// The tensors are written once into a buffer owned by Rust, which ``BatchBuffer/data``
+    /// wraps without copying it. It fails with `.int32` if an id doesn't fit in an `Int32`.
+    ///
+    /// The encodings are padded with ``padding`` if set, or to the longest one of the batch
+    /// otherwise.
+    ///
+    /// - Parameters:
+    ///
+    ///   - inputs:
+    ///         A list of single sequences to encode.
+    ///
+    ///   - addSpecialTokens:
+    ///         Whether to add the special tokens
+    ///
+    ///   - dtype:
+    ///         The type of the elements of the tensor
+    ///
+    ///   - withPositionIds:
+    ///         Whether to add the `position_ids` tensor
+    ///
+    /// - Returns:
+    ///     The tensors of the batch
+    ///
+    public func encodeBatchToBuffer(
+        _ inputs: [InputSequence], addSpecialTokens: Bool = true, dtype: BufferDType = .int64,
+        withPositionIds: Bool = false
+    ) throws -> BatchBuffer {
+        let inputs = inputs.map { RustEncodeInput.single(input: $0.toRustInputSequence()) }
+        return BatchBuffer(
+            try self.tokenizer.encodeBatchToBuffer(
+                inputs: inputs, addSpecialTokens: addSpecialTokens, dtype: dtype,
+                withPositionIds: withPositionIds))
+    }
+
+    /// Encode any number of segments at once, e.g. a system prompt, a context and a question.
+    ///
+    /// Each segment is encoded on its own, then the template lays them out along with the
+    /// special tokens. It follows the ``TemplateProcessing`` syntax, with a letter per segment:
+    ///
+    /// ```
+    /// encode(
+    ///   segments: ["You are helpful", "Some context", "A question"],
+    ///   template: "[CLS] $A [SEP] $B:1 [SEP]:1 $C:2 [SEP]:2"
+    /// )
+    /// ```
+    ///
+    /// - Parameters:
+    ///
+    ///   - segments:
+    ///         The input sequences we want to encode. Each of them can be either raw text or
+    ///         pre-tokenized.
+    ///
+    ///   - template:
+    ///         How to lay out the segments and the special tokens, which must be the ones of
+    ///         the ``TemplateProcessing`` post-processor. By default, the segments are
+    ///         concatenated and each of them gets its index as type id.
+    ///
+    ///   - addSpecialTokens:
+    ///         Whether to add the special tokens
+    ///
+    /// - Returns:
+    ///     The encoded result
+    ///
+    public func encode(
+        segments: [InputSequence], template: String? = nil, addSpecialTokens: Bool = true
+    ) throws -> Encoding {
+        let encoding = try self.tokenizer.encodeSegments(
+            segments: segments.map { $0.toRustInputSequence() },
+            template: template,
             addSpecialTokens: addSpecialTokens)
         return Encoding(encoding)
     }
@@ -155,6 +520,90 @@ public class Tokenizer {
         return try self.tokenizer.decode(ids: ids, skipSpecialTokens: skipSpecialTokens)
     }
 
+    /// Create a stream to decode ids one at a time, as a model generates them
+    ///
+    /// ```
+    /// let stream = tokenizer.decodeStream()
+    /// for id in generatedIds {
+    ///     if let text = try stream.step(id) { print(text, terminator: "") }
+    /// }
+    /// if let text = try stream.flush() { print(text) }
+    /// ```
+    ///
+    /// - Parameters:
+    ///     - skipSpecialTokens:
+    ///         Whether the special tokens should be removed from the decoded text
+    public func decodeStream(skipSpecialTokens: Bool = false) -> DecodeStream {
+        DecodeStream(self.tokenizer.decodeStream(skipSpecialTokens: skipSpecialTokens))
+    }
+
+    /// Create a matcher looking for stop strings in the ids generated by a model
+    ///
+    /// - Parameters:
+    ///     - stops:
+    ///         The stop strings, none of which can be empty
+    ///     - skipSpecialTokens:
+    ///         Whether the special tokens should be removed from the decoded text
+    public func stopSequenceMatcher(_ stops: [String], skipSpecialTokens: Bool = false) throws
+        -> StopSequenceMatcher
+    {
+        StopSequenceMatcher(
+            try self.tokenizer.stopSequenceMatcher(
+                stops: stops, skipSpecialTokens: skipSpecialTokens))
+    }
+
+    /// Decode a batch of ids back to their corresponding string
+    ///
+    /// - Parameters:
+    ///     - sentences:
+    ///         The list of sequences we want to decode
+    ///
+    ///     - skipSpecialTokens:
+    ///         Whether the special tokens should be removed from the decoded strings
+    ///
+    /// - Returns:
+    ///      A list of decoded strings
+    public func decodeBatch(_ sentences: [[UInt32]], skipSpecialTokens: Bool) throws -> [String] {
+        return try self.tokenizer.decodeBatch(
+            sentences: sentences, skipSpecialTokens: skipSpecialTokens)
+    }
+
+    /// Decode the given list of ids, keeping track of the text added by each of them
+    ///
+    /// Joining the pieces gives the same string as ``decode(_:skipSpecialTokens:)``. An id
+    /// that doesn't add any text on its own (a skipped special token, or the first bytes of a
+    /// character) gets an empty piece, and the text goes to the id completing it.
+    ///
+    /// - Parameters:
+    ///     - ids:
+    ///         The list of ids that we want to decode
+    ///
+    ///     - skipSpecialTokens:
+    ///         Whether the special tokens should be removed from the decoded string
+    ///
+    /// - Returns:
+    ///      A list of tuple with each id, its decoded text and the offsets of this text
+    ///      in the decoded string, in unicode scalars
+    public func decodePieces(_ ids: [UInt32], skipSpecialTokens: Bool) throws
+        -> [(id: UInt32, text: String, offsets: Offsets)]
+    {
+        try self.tokenizer.decodePieces(ids: ids, skipSpecialTokens: skipSpecialTokens).map {
+            ($0.id, $0.text, (start: Int($0.offsets[0]), end: Int($0.offsets[1])))
+        }
+    }
+
+    /// Return the number of special tokens that would be added for single/pair sentences.
+    ///
+    /// - Parameters:
+    ///     - isPair:
+    ///         Whether the input would be a pair of sequences
+    ///
+    /// - Returns:
+    ///     The number of tokens to add
+    public func numSpecialTokensToAdd(isPair: Bool) -> Int {
+        Int(self.tokenizer.numSpecialTokensToAdd(isPair: isPair))
+    }
+
     /// Train the Tokenizer using the given files.
     ///
     /// Reads the files line by line, while keeping all the whitespace, even new lines.
@@ -167,7 +616,7 @@ public class Tokenizer {
     ///
     ///     - trainer:
     ///         An optional trainer that should be used to train our Model
-    public func train(files: [String], trainer: BPETrainer? = nil) throws {
+    public func train(files: [String], trainer: Trainer? = nil) throws {
         try self.tokenizer.train(files: files, trainer: trainer?.trainer)
     }
 
@@ -260,6 +709,173 @@ public struct Encoding {
     public var attentionMask: [UInt32] {
         self.encoding.getAttentionMask()
     }
+
+    /// The offsets of each token in its input sequence.
+    public var offsets: [Offsets] {
+        self.encoding.getOffsets().map { Encoding.offsets($0) }
+    }
+
+    /// The index of the word each token comes from, or `nil` for the special tokens.
+    public var wordIds: [UInt32?] {
+        self.encoding.getWordIds()
+    }
+
+    /// The index of the sequence each token comes from, or `nil` for the special tokens.
+    public var sequenceIds: [Int?] {
+        self.encoding.getSequenceIds().map { $0.map { Int($0) } }
+    }
+
+    public var specialTokensMask: [UInt32] {
+        self.encoding.getSpecialTokensMask()
+    }
+
+    /// The number of input sequences of this encoding.
+    public var nSequences: Int {
+        Int(self.encoding.getNSequences())
+    }
+
+    /// Get the index of the sequence containing the given token.
+    public func tokenToSequence(_ tokenIndex: Int) -> Int? {
+        self.encoding.tokenToSequence(tokenIndex: UInt64(tokenIndex)).map { Int($0) }
+    }
+
+    /// Get the offsets of the given token in its input sequence.
+    ///
+    /// - Parameters:
+    ///     - tokenIndex:
+    ///         The index of a token in the encoding
+    ///     - sequenceIndex:
+    ///         If given, the token must belong to this input sequence
+    public func tokenToChars(_ tokenIndex: Int, sequenceIndex: Int? = nil) -> Offsets? {
+        self.encoding.tokenToChars(
+            tokenIndex: UInt64(tokenIndex), sequenceIndex: sequenceIndex.map { UInt64($0) }
+        ).map { Encoding.offsets($0) }
+    }
+
+    /// Get the index of the word containing the given token in its input sequence.
+    ///
+    /// - Parameters:
+    ///     - tokenIndex:
+    ///         The index of a token in the encoding
+    ///     - sequenceIndex:
+    ///         If given, the token must belong to this input sequence
+    public func tokenToWord(_ tokenIndex: Int, sequenceIndex: Int? = nil) -> UInt32? {
+        self.encoding.tokenToWord(
+            tokenIndex: UInt64(tokenIndex), sequenceIndex: sequenceIndex.map { UInt64($0) })
+    }
+
+    /// Get the index of the token containing the given char of an input sequence.
+    ///
+    /// - Parameters:
+    ///     - charPos:
+    ///         The position of a char in the input sequence
+    ///     - sequenceIndex:
+    ///         The index of the input sequence, the first one by default
+    public func charToToken(_ charPos: Int, sequenceIndex: Int? = nil) -> Int? {
+        self.encoding.charToToken(
+            charPos: UInt64(charPos), sequenceIndex: sequenceIndex.map { UInt64($0) }
+        ).map { Int($0) }
+    }
+
+    /// Get the index of the word containing the given char of an input sequence.
+    ///
+    /// - Parameters:
+    ///     - charPos:
+    ///         The position of a char in the input sequence
+    ///     - sequenceIndex:
+    ///         The index of the input sequence, the first one by default
+    public func charToWord(_ charPos: Int, sequenceIndex: Int? = nil) -> UInt32? {
+        self.encoding.charToWord(
+            charPos: UInt64(charPos), sequenceIndex: sequenceIndex.map { UInt64($0) })
+    }
+
+    /// Get the range of tokens of the given word of an input sequence.
+    ///
+    /// - Parameters:
+    ///     - wordIndex:
+    ///         The index of a word in the input sequence
+    ///     - sequenceIndex:
+    ///         The index of the input sequence, the first one by default
+    public func wordToTokens(_ wordIndex: UInt32, sequenceIndex: Int? = nil) -> Range<Int>? {
+        self.encoding.wordToTokens(
+            wordIndex: wordIndex, sequenceIndex: sequenceIndex.map { UInt64($0) }
+        ).map { Int($0[0])..<Int($0[1]) }
+    }
+
+    /// Get the offsets of the given word of an input sequence.
+    ///
+    /// - Parameters:
+    ///     - wordIndex:
+    ///         The index of a word in the input sequence
+    ///     - sequenceIndex:
+    ///         The index of the input sequence, the first one by default
+    public func wordToChars(_ wordIndex: UInt32, sequenceIndex: Int? = nil) -> Offsets? {
+        self.encoding.wordToChars(
+            wordIndex: wordIndex, sequenceIndex: sequenceIndex.map { UInt64($0) }
+        ).map { Encoding.offsets($0) }
+    }
+
+    static func offsets(_ offsets: RustOffsets) -> Offsets {
+        (start: Int(offsets[0]), end: Int(offsets[1]))
+    }
+
+    /// The encodings of the tokens which didn't fit when truncating, if any.
+    public var overflowing: [Encoding] {
+        self.encoding.getOverflowing().map { Encoding($0) }
+    }
+}
+
+/// Decodes the ids generated by a model one at a time.
+///
+/// See ``Tokenizer/decodeStream(skipSpecialTokens:)``.
+public class DecodeStream {
+    let stream: RustDecodeStream
+
+    init(_ stream: RustDecodeStream) {
+        self.stream = stream
+    }
+
+    /// Returns the text completed by the given id, or `nil` while it is pending, e.g. when
+    /// it holds the first bytes of a character.
+    public func step(_ id: UInt32) throws -> String? {
+        try self.stream.step(id: id)
+    }
+
+    /// Returns the text of the pending ids, if any, once the generation is over.
+    public func flush() throws -> String? {
+        try self.stream.flush()
+    }
+}
+
+/// Where a stop string was found in the output of a ``StopSequenceMatcher``.
+public struct StopMatch {
+    public let stop: String
+    /// The position of the stop string in the decoded output, in unicode scalars.
+    public let charIndex: Int
+    /// The index of the first id decoding to the stop string. This id may also decode to some
+    /// text before the stop string.
+    public let tokenIndex: Int
+}
+
+/// Follows the ids generated by a model, and reports the first stop string appearing in the
+/// decoded output, even when it spans several tokens.
+///
+/// See ``Tokenizer/stopSequenceMatcher(_:skipSpecialTokens:)``.
+public class StopSequenceMatcher {
+    let matcher: RustStopSequenceMatcher
+
+    init(_ matcher: RustStopSequenceMatcher) {
+        self.matcher = matcher
+    }
+
+    /// Returns the stop string completed by the given id, if any. Once a stop string is
+    /// found, the next ids are ignored and the same match is returned.
+    public func step(_ id: UInt32) throws -> StopMatch? {
+        try self.matcher.step(id: id).map {
+            StopMatch(
+                stop: $0.stop, charIndex: Int($0.charIndex), tokenIndex: Int($0.tokenIndex))
+        }
+    }
 }
 
 /// Represents a token that can be be added to a ``Tokenizer``.
@@ -331,18 +947,72 @@ public struct AddedToken {
 
 //MARK:- Models
 
-/// A [Byte-Pair Encoding (BPE)](https://aclanthology.org/P16-1162/) model.
-public class BPE {
-    let model: RustBpe
+/// Base class for all models
+///
+/// The model represents the actual tokenization algorithm. This is the part that
+/// will contain and manage the learned vocabulary.
+public class Model {
+    let model: RustModel
 
-    /// Read a `vocab.json` and a `merges.txt` files.
-    ///
-    /// This method provides a way to read and parse the content of these files,
-    /// returning the relevant data structures. If you want to instantiate some BPE models
-    /// from memory, this method gives you the expected input from the standard files.
-    ///
-    /// - Parameters:
-    ///     - vocabPath:
+    init(model: RustModel) {
+        self.model = model
+    }
+
+    /// Wrap the given model into the most specific subclass available.
+    static func wrap(_ model: RustModel) -> Model {
+        if let bpe = model.asBpe() {
+            return BPE(bpe: bpe)
+        }
+        if let wordPiece = model.asWordpiece() {
+            return WordPiece(wordPiece: wordPiece)
+        }
+        if let unigram = model.asUnigram() {
+            return Unigram(unigram: unigram)
+        }
+        if let wordLevel = model.asWordlevel() {
+            return WordLevel(wordLevel: wordLevel)
+        }
+        return Model(model: model)
+    }
+
+    /// Get the ID associated to a token
+    public func tokenToId(_ token: String) -> UInt32? {
+        self.model.tokenToId(token: token)
+    }
+
+    /// Get the token associated to an ID
+    public func idToToken(_ id: UInt32) -> String? {
+        self.model.idToToken(id: id)
+    }
+
+    /// The size of the vocabulary
+    public var vocabSize: Int {
+        Int(self.model.getVocabSize())
+    }
+
+    /// Save the current model in the given folder, using the given prefix for the
+    /// various files that will get created.
+    ///
+    /// - Returns:
+    ///     The list of saved files
+    @discardableResult
+    public func save(folder: String, prefix: String? = nil) throws -> [String] {
+        try self.model.save(folder: folder, prefix: prefix)
+    }
+}
+
+/// A [Byte-Pair Encoding (BPE)](https://aclanthology.org/P16-1162/) model.
+public class BPE: Model {
+    let bpe: RustBpe
+
+    /// Read a `vocab.json` and a `merges.txt` files.
+    ///
+    /// This method provides a way to read and parse the content of these files,
+    /// returning the relevant data structures. If you want to instantiate some BPE models
+    /// from memory, this method gives you the expected input from the standard files.
+    ///
+    /// - Parameters:
+    ///     - vocabPath:
     ///         The path to a `vocab.json` file
     ///
     ///     - mergesPath:
@@ -396,7 +1066,7 @@ public class BPE {
             dropout: dropout, unkToken: unkToken, continuingSubwordPrefix: continuingSubwordPrefix,
             endOfWordSuffix: endOfWordSuffix, fuseUnk: fuseUnk)
 
-        self.init(model: model)
+        self.init(bpe: model)
     }
 
     /// Instantiate a BPE model from the given files.
@@ -456,15 +1126,247 @@ public class BPE {
             cacheCapacity: cacheCapacity,
             dropout: dropout, unkToken: unkToken, continuingSubwordPrefix: continuingSubwordPrefix,
             endOfWordSuffix: endOfWordSuffix, fuseUnk: fuseUnk)
-        self.init(model: model)
+        self.init(bpe: model)
     }
 
-    init(model: RustBpe) {
-        self.model = model
+    init(bpe: RustBpe) {
+        self.bpe = bpe
+        super.init(model: bpe.asModel())
     }
 
     public var unkToken: String? {
-        self.model.getUnkToken()
+        self.bpe.getUnkToken()
+    }
+}
+
+/// An implementation of the WordPiece algorithm
+public class WordPiece: Model {
+    let wordPiece: RustWordPiece
+
+    /// Read a `vocab.txt` file
+    ///
+    /// This method provides a way to read and parse the content of a standard `vocab.txt`
+    /// file as used by the WordPiece Model, returning the relevant data structures. If you
+    /// want to instantiate some WordPiece models from memory, this method gives you the
+    /// expected input from the standard files.
+    ///
+    /// - Parameters:
+    ///     - vocabPath:
+    ///         The path to a `vocab.txt` file
+    ///
+    /// - Returns:
+    ///     The vocabulary loaded into memory
+    public static func read_file(vocabFileWithPath vocabPath: String) throws -> Vocab {
+        try modelsWordpieceWordpieceReadFile(vocab: vocabPath)
+    }
+
+    /// Instantiate a WordPiece model from the given vocabulary.
+    ///
+    /// - Parameters:
+    ///     - vocab:
+    ///         A dictionary of string keys and their ids `["am": 0, ...]`
+    ///
+    ///     - unkToken:
+    ///         The unknown token to be used by the model.
+    ///
+    ///     - maxInputCharsPerWord:
+    ///         The maximum number of characters to authorize in a single word.
+    ///
+    ///     - continuingSubwordPrefix:
+    ///         The prefix to attach to subword units that don't represent a beginning of word.
+    public convenience init(
+        vocab: Vocab? = nil,
+        unkToken: String? = nil,
+        maxInputCharsPerWord: UInt64? = nil,
+        continuingSubwordPrefix: String? = nil
+    ) throws {
+        let model = try RustWordPiece(
+            vocab: vocab, vocabFile: nil, unkToken: unkToken,
+            maxInputCharsPerWord: maxInputCharsPerWord,
+            continuingSubwordPrefix: continuingSubwordPrefix)
+        self.init(wordPiece: model)
+    }
+
+    /// Instantiate a WordPiece model from the given `vocab.txt` file.
+    ///
+    /// This method is roughly equivalent to doing:
+    ///
+    /// ```
+    /// let vocab = WordPiece.read_file(vocabFileWithPath: vocab_filename)
+    /// let wordPiece = WordPiece(vocab: vocab)
+    /// ```
+    ///
+    /// - Parameters:
+    ///     - vocabFileWithPath:
+    ///         The path to a `vocab.txt` file
+    ///
+    ///     - unkToken:
+    ///         The unknown token to be used by the model.
+    ///
+    ///     - maxInputCharsPerWord:
+    ///         The maximum number of characters to authorize in a single word.
+    ///
+    ///     - continuingSubwordPrefix:
+    ///         The prefix to attach to subword units that don't represent a beginning of word.
+    public convenience init(
+        vocabFileWithPath vocabFile: String,
+        unkToken: String? = nil,
+        maxInputCharsPerWord: UInt64? = nil,
+        continuingSubwordPrefix: String? = nil
+    ) throws {
+        let model = try RustWordPiece(
+            vocab: nil, vocabFile: vocabFile, unkToken: unkToken,
+            maxInputCharsPerWord: maxInputCharsPerWord,
+            continuingSubwordPrefix: continuingSubwordPrefix)
+        self.init(wordPiece: model)
+    }
+
+    init(wordPiece: RustWordPiece) {
+        self.wordPiece = wordPiece
+        super.init(model: wordPiece.asModel())
+    }
+
+    public var unkToken: String {
+        self.wordPiece.getUnkToken()
+    }
+
+    public var continuingSubwordPrefix: String {
+        self.wordPiece.getContinuingSubwordPrefix()
+    }
+
+    public var maxInputCharsPerWord: UInt64 {
+        self.wordPiece.getMaxInputCharsPerWord()
+    }
+}
+
+/// An implementation of the Unigram algorithm
+public class Unigram: Model {
+    let unigram: RustUnigram
+
+    /// Instantiate a Unigram model.
+    ///
+    /// - Parameters:
+    ///     - vocab:
+    ///         A list of vocabulary items and their relative score `[("am", -0.2442), ...]`
+    ///
+    ///     - unkId:
+    ///         The unknown token id to be used by the model.
+    ///
+    ///     - byteFallback:
+    ///         Whether to use byte-fallback trick (defaults to `false`).
+    public convenience init(
+        vocab: [(String, Double)]? = nil,
+        unkId: UInt64? = nil,
+        byteFallback: Bool? = nil
+    ) throws {
+        let vocab = vocab?.map { RustUnigramPiece(piece: $0.0, score: $0.1) }
+        let model = try RustUnigram(vocab: vocab, unkId: unkId, byteFallback: byteFallback)
+        self.init(unigram: model)
+    }
+
+    init(unigram: RustUnigram) {
+        self.unigram = unigram
+        super.init(model: unigram.asModel())
+    }
+
+    /// The vocabulary items and their score, ordered by id.
+    public var vocab: [(String, Double)] {
+        self.unigram.getPieces().map { ($0.piece, $0.score) }
+    }
+
+    /// The score of the given piece, or `nil` if the piece isn't in the vocabulary.
+    public func score(of piece: String) -> Double? {
+        self.unigram.getScore(piece: piece)
+    }
+
+    public var unkId: UInt64? {
+        self.unigram.getUnkId()
+    }
+
+    public var byteFallback: Bool {
+        self.unigram.getByteFallback()
+    }
+
+    /// Compute the `n` most likely segmentations of the given sentence, best first.
+    ///
+    /// This is useful for subword regularization. The sentence is segmented as a whole,
+    /// so it is expected to be already normalized and pre-tokenized. Unknown characters
+    /// are reported like `tokenize` does, as bytes with `byteFallback`, or as the unknown
+    /// token, and the segmentation fails if the model has no `unkId`.
+    ///
+    /// - Parameters:
+    ///     - sentence:
+    ///         The sentence to segment
+    ///
+    ///     - n:
+    ///         The maximum number of segmentations to return
+    ///
+    /// - Returns:
+    ///     The segmentations along with their log-probability
+    public func nbest(_ sentence: String, n: Int) throws -> [(
+        tokens: [String], ids: [UInt32], logProb: Double
+    )] {
+        try self.unigram.nbest(sentence: sentence, n: UInt64(n)).map {
+            (tokens: $0.tokens, ids: $0.ids, logProb: $0.logProb)
+        }
+    }
+}
+
+/// An implementation of the WordLevel algorithm
+///
+/// Most simple tokenizer model based on mapping tokens to their corresponding id.
+public class WordLevel: Model {
+    let wordLevel: RustWordLevel
+
+    /// Read a `vocab.json` file
+    ///
+    /// This method provides a way to read and parse the content of a vocabulary file,
+    /// returning the relevant data structures. If you want to instantiate some WordLevel
+    /// models from memory, this method gives you the expected input from the standard files.
+    ///
+    /// - Parameters:
+    ///     - vocabPath:
+    ///         The path to a `vocab.json` file
+    ///
+    /// - Returns:
+    ///     The vocabulary loaded into memory
+    public static func read_file(vocabFileWithPath vocabPath: String) throws -> Vocab {
+        try modelsWordlevelWordlevelReadFile(vocab: vocabPath)
+    }
+
+    /// Instantiate a WordLevel model from the given vocabulary.
+    ///
+    /// - Parameters:
+    ///     - vocab:
+    ///         A dictionary of string keys and their ids `["am": 0, ...]`
+    ///
+    ///     - unkToken:
+    ///         The unknown token to be used by the model.
+    public convenience init(vocab: Vocab? = nil, unkToken: String? = nil) throws {
+        let model = try RustWordLevel(vocab: vocab, vocabFile: nil, unkToken: unkToken)
+        self.init(wordLevel: model)
+    }
+
+    /// Instantiate a WordLevel model from the given `vocab.json` file.
+    ///
+    /// - Parameters:
+    ///     - vocabFileWithPath:
+    ///         The path to a `vocab.json` file
+    ///
+    ///     - unkToken:
+    ///         The unknown token to be used by the model.
+    public convenience init(vocabFileWithPath vocabFile: String, unkToken: String? = nil) throws {
+        let model = try RustWordLevel(vocab: nil, vocabFile: vocabFile, unkToken: unkToken)
+        self.init(wordLevel: model)
+    }
+
+    init(wordLevel: RustWordLevel) {
+        self.wordLevel = wordLevel
+        super.init(model: wordLevel.asModel())
+    }
+
+    public var unkToken: String {
+        self.wordLevel.getUnkToken()
     }
 }
 
@@ -504,9 +1406,18 @@ public enum AddedTokenOrString: ExpressibleByStringLiteral, CustomStringConverti
     }
 }
 
+/// Base class for all trainers
+public class Trainer {
+    let trainer: RustTrainer
+
+    init(trainer: RustTrainer) {
+        self.trainer = trainer
+    }
+}
+
 /// Trainer capable of training a BPE model
-public class BPETrainer {
-    let trainer: RustBpeTrainer
+public class BPETrainer: Trainer {
+    let bpeTrainer: RustBpeTrainer
 
     /// Instantiate a trainer.
     ///
@@ -529,15 +1440,14 @@ public class BPETrainer {
     ///     - initialAlphabet:
     ///         A list of characters to include in the initial alphabet, even
     ///         if not seen in the training dataset.
-    ///         If the strings contain more than one character, only the first one
-    ///         is kept.
+    ///         Each string must be a single character.
     ///
     ///     - continuingSubwordPrefix:
     ///         A prefix to be used for every subword that is not a beginning-of-word.
     ///
     ///     - endOfWordSuffix:
     ///         A suffix to be used for every subword that is a end-of-word.
-    public init(
+    public convenience init(
         vocabSize: UInt64? = nil,
         minFrequency: UInt32? = nil,
         showProgress: Bool? = nil,
@@ -551,25 +1461,1571 @@ public class BPETrainer {
             AddedTokenOrString.toRustAddedTokens($0, special: true)
         })
 
-        self.trainer = try RustBpeTrainer(
+        let trainer = try RustBpeTrainer(
             vocabSize: vocabSize, minFrequency: minFrequency,
             showProgress: showProgress, specialTokens: specialTokens, limitAlphabet: limitAlphabet,
             initialAlphabet: initialAlphabet, continuingSubwordPrefix: continuingSubwordPrefix,
             endOfWordSuffix: endOfWordSuffix)
+        self.init(bpeTrainer: trainer)
+    }
+
+    init(bpeTrainer: RustBpeTrainer) {
+        self.bpeTrainer = bpeTrainer
+        super.init(trainer: bpeTrainer.asTrainer())
     }
 }
 
-//MARK:- Pre-Tokenizers
+/// Trainer capable of training a WordLevel model
+public class WordLevelTrainer: Trainer {
+    let wordLevelTrainer: RustWordLevelTrainer
 
-/// This pre-tokenizer simply splits using the following regex: `\w+|[^\w\s]+`
-public class Whitespace {
-    let preTokenizer: RustWhitespace
+    /// Instantiate a trainer.
+    ///
+    /// - Parameters:
+    ///     - vocabSize:
+    ///         The size of the final vocabulary, including all tokens and alphabet.
+    ///
+    ///     - minFrequency:
+    ///         The minimum frequency a word should have in order to be kept.
+    ///
+    ///     - showProgress:
+    ///         Whether to show progress bars while training.
+    ///
+    ///     - specialTokens:
+    ///         A list of special tokens the model should know of.
+    public convenience init(
+        vocabSize: UInt64? = nil,
+        minFrequency: UInt32? = nil,
+        showProgress: Bool? = nil,
+        specialTokens: [AddedTokenOrString]? = nil
+    ) throws {
+        let specialTokens = specialTokens.map({
+            AddedTokenOrString.toRustAddedTokens($0, special: true)
+        })
 
-    public convenience init() {
-        self.init(preTokenizer: RustWhitespace())
+        let trainer = try RustWordLevelTrainer(
+            vocabSize: vocabSize, minFrequency: minFrequency,
+            showProgress: showProgress, specialTokens: specialTokens)
+        self.init(wordLevelTrainer: trainer)
     }
 
-    init(preTokenizer: RustWhitespace) {
-        self.preTokenizer = preTokenizer
+    init(wordLevelTrainer: RustWordLevelTrainer) {
+        self.wordLevelTrainer = wordLevelTrainer
+        super.init(trainer: wordLevelTrainer.asTrainer())
+    }
+
+    public var vocabSize: UInt64 {
+        self.wordLevelTrainer.getVocabSize()
+    }
+
+    public var minFrequency: UInt32 {
+        self.wordLevelTrainer.getMinFrequency()
+    }
+}
+
+/// Trainer capable of training a WordPiece model
+public class WordPieceTrainer: Trainer {
+    let wordPieceTrainer: RustWordPieceTrainer
+
+    /// Instantiate a trainer.
+    ///
+    /// - Parameters:
+    ///     - vocabSize:
+    ///         The size of the final vocabulary, including all tokens and alphabet.
+    ///
+    ///     - minFrequency:
+    ///         The minimum frequency a pair should have in order to be merged.
+    ///
+    ///     - showProgress:
+    ///         Whether to show progress bars while training.
+    ///
+    ///     - specialTokens:
+    ///         A list of special tokens the model should know of.
+    ///
+    ///     - limitAlphabet:
+    ///         The maximum different characters to keep in the alphabet.
+    ///
+    ///     - initialAlphabet:
+    ///         A list of characters to include in the initial alphabet, even
+    ///         if not seen in the training dataset.
+    ///         Each string must be a single character.
+    ///
+    ///     - continuingSubwordPrefix:
+    ///         A prefix to be used for every subword that is not a beginning-of-word.
+    ///
+    ///     - endOfWordSuffix:
+    ///         A suffix to be used for every subword that is a end-of-word.
+    public convenience init(
+        vocabSize: UInt64? = nil,
+        minFrequency: UInt32? = nil,
+        showProgress: Bool? = nil,
+        specialTokens: [AddedTokenOrString]? = nil,
+        limitAlphabet: UInt64? = nil,
+        initialAlphabet: [String]? = nil,
+        continuingSubwordPrefix: String? = nil,
+        endOfWordSuffix: String? = nil
+    ) throws {
+        let specialTokens = specialTokens.map({
+            AddedTokenOrString.toRustAddedTokens($0, special: true)
+        })
+
+        let trainer = try RustWordPieceTrainer(
+            vocabSize: vocabSize, minFrequency: minFrequency,
+            showProgress: showProgress, specialTokens: specialTokens, limitAlphabet: limitAlphabet,
+            initialAlphabet: initialAlphabet, continuingSubwordPrefix: continuingSubwordPrefix,
+            endOfWordSuffix: endOfWordSuffix)
+        self.init(wordPieceTrainer: trainer)
+    }
+
+    init(wordPieceTrainer: RustWordPieceTrainer) {
+        self.wordPieceTrainer = wordPieceTrainer
+        super.init(trainer: wordPieceTrainer.asTrainer())
+    }
+
+    public var vocabSize: UInt64 {
+        self.wordPieceTrainer.getVocabSize()
+    }
+
+    public var minFrequency: UInt32 {
+        self.wordPieceTrainer.getMinFrequency()
+    }
+}
+
+/// Trainer capable of training a Unigram model
+public class UnigramTrainer: Trainer {
+    let unigramTrainer: RustUnigramTrainer
+
+    /// Instantiate a trainer.
+    ///
+    /// - Parameters:
+    ///     - vocabSize:
+    ///         The size of the final vocabulary, including all tokens and alphabet.
+    ///
+    ///     - showProgress:
+    ///         Whether to show progress bars while training.
+    ///
+    ///     - specialTokens:
+    ///         A list of special tokens the model should know of.
+    ///
+    ///     - initialAlphabet:
+    ///         A list of characters to include in the initial alphabet, even
+    ///         if not seen in the training dataset.
+    ///         Each string must be a single character.
+    ///
+    ///     - shrinkingFactor:
+    ///         The shrinking factor used at each step of the training to prune the
+    ///         vocabulary.
+    ///
+    ///     - unkToken:
+    ///         The token used for out-of-vocabulary tokens.
+    ///
+    ///     - maxPieceLength:
+    ///         The maximum length of a given token.
+    ///
+    ///     - nSubIterations:
+    ///         The number of iterations of the EM algorithm to perform before
+    ///         pruning the vocabulary.
+    ///
+    ///     - seedSize:
+    ///         The number of pieces of the initial vocabulary, built from the most
+    ///         frequent substrings of the dataset.
+    public convenience init(
+        vocabSize: UInt32? = nil,
+        showProgress: Bool? = nil,
+        specialTokens: [AddedTokenOrString]? = nil,
+        initialAlphabet: [String]? = nil,
+        shrinkingFactor: Double? = nil,
+        unkToken: String? = nil,
+        maxPieceLength: UInt64? = nil,
+        nSubIterations: UInt32? = nil,
+        seedSize: UInt64? = nil
+    ) throws {
+        let specialTokens = specialTokens.map({
+            AddedTokenOrString.toRustAddedTokens($0, special: true)
+        })
+
+        let trainer = try RustUnigramTrainer(
+            vocabSize: vocabSize, showProgress: showProgress, specialTokens: specialTokens,
+            initialAlphabet: initialAlphabet, shrinkingFactor: shrinkingFactor,
+            unkToken: unkToken, maxPieceLength: maxPieceLength, nSubIterations: nSubIterations,
+            seedSize: seedSize)
+        self.init(unigramTrainer: trainer)
+    }
+
+    init(unigramTrainer: RustUnigramTrainer) {
+        self.unigramTrainer = unigramTrainer
+        super.init(trainer: unigramTrainer.asTrainer())
+    }
+
+    public var vocabSize: UInt32 {
+        self.unigramTrainer.getVocabSize()
+    }
+
+    public var shrinkingFactor: Double {
+        self.unigramTrainer.getShrinkingFactor()
+    }
+
+    public var unkToken: String? {
+        self.unigramTrainer.getUnkToken()
+    }
+
+    public var maxPieceLength: UInt64 {
+        self.unigramTrainer.getMaxPieceLength()
+    }
+
+    public var nSubIterations: UInt32 {
+        self.unigramTrainer.getNSubIterations()
+    }
+
+    public var seedSize: UInt64 {
+        self.unigramTrainer.getSeedSize()
+    }
+}
+
+//MARK:- Normalizers
+
+/// A pattern used to match parts of a string.
+public enum Pattern: ExpressibleByStringLiteral {
+    /// Matches the given string literally
+    case string(String)
+    /// Matches the given regular expression
+    case regex(String)
+
+    public init(stringLiteral value: String) {
+        self = .string(value)
+    }
+
+    init(_ pattern: RustPattern) {
+        switch pattern {
+        case .string(let value):
+            self = .string(value)
+        case .regex(let pattern):
+            self = .regex(pattern)
+        }
+    }
+
+    func toRustPattern() -> RustPattern {
+        switch self {
+        case .string(let value):
+            return RustPattern.string(value: value)
+        case .regex(let pattern):
+            return RustPattern.regex(pattern: pattern)
+        }
+    }
+}
+
+/// Base class for all normalizers
+public class Normalizer {
+    let normalizer: RustNormalizer
+
+    init(normalizer: RustNormalizer) {
+        self.normalizer = normalizer
+    }
+
+    /// Wrap the given normalizer into the most specific subclass available.
+    static func wrap(_ normalizer: RustNormalizer) -> Normalizer {
+        if let n = normalizer.asBertNormalizer() {
+            return BertNormalizer(bertNormalizer: n)
+        }
+        if let n = normalizer.asPrecompiled() {
+            return Precompiled(precompiled: n)
+        }
+        if let n = normalizer.asNfc() {
+            return NFC(nfc: n)
+        }
+        if let n = normalizer.asNfd() {
+            return NFD(nfd: n)
+        }
+        if let n = normalizer.asNfkc() {
+            return NFKC(nfkc: n)
+        }
+        if let n = normalizer.asNfkd() {
+            return NFKD(nfkd: n)
+        }
+        if let n = normalizer.asLowercase() {
+            return Lowercase(lowercase: n)
+        }
+        if let n = normalizer.asStrip() {
+            return Strip(strip: n)
+        }
+        if let n = normalizer.asStripAccents() {
+            return StripAccents(stripAccents: n)
+        }
+        if let n = normalizer.asReplace() {
+            return Replace(replace: n)
+        }
+        if let n = normalizer.asPrepend() {
+            return Prepend(prepend: n)
+        }
+        if let n = normalizer.asSequence() {
+            return NormalizerSequence(sequence: n)
+        }
+        return Normalizer(normalizer: normalizer)
+    }
+
+    /// Normalize the given string
+    ///
+    /// This method provides a way to visualize the effect of a ``Normalizer`` but it does not
+    /// keep track of the alignment information.
+    ///
+    /// - Parameters:
+    ///     - sequence:
+    ///         A string to normalize
+    ///
+    /// - Returns:
+    ///     A string after normalization
+    public func normalize(_ sequence: String) throws -> String {
+        try self.normalizer.normalizeStr(sequence: sequence)
+    }
+}
+
+/// BertNormalizer
+///
+/// Takes care of normalizing raw text before giving it to a Bert model.
+/// This includes cleaning the text, handling accents, chinese chars and lowercasing.
+public class BertNormalizer: Normalizer {
+    let bertNormalizer: RustBertNormalizer
+
+    /// - Parameters:
+    ///     - cleanText:
+    ///         Whether to clean the text, by removing any control characters
+    ///         and replacing all whitespaces by the classic one.
+    ///
+    ///     - handleChineseChars:
+    ///         Whether to handle chinese chars by putting spaces around them.
+    ///
+    ///     - stripAccents:
+    ///         Whether to strip all accents. If this option is not specified (ie == `nil`),
+    ///         then it will be determined by the value for `lowercase` (as in the original Bert).
+    ///
+    ///     - lowercase:
+    ///         Whether to lowercase.
+    public convenience init(
+        cleanText: Bool = true,
+        handleChineseChars: Bool = true,
+        stripAccents: Bool? = nil,
+        lowercase: Bool = true
+    ) {
+        self.init(
+            bertNormalizer: RustBertNormalizer(
+                cleanText: cleanText, handleChineseChars: handleChineseChars,
+                stripAccents: stripAccents, lowercase: lowercase))
+    }
+
+    init(bertNormalizer: RustBertNormalizer) {
+        self.bertNormalizer = bertNormalizer
+        super.init(normalizer: bertNormalizer.asNormalizer())
+    }
+
+    public var cleanText: Bool {
+        self.bertNormalizer.getCleanText()
+    }
+
+    public var handleChineseChars: Bool {
+        self.bertNormalizer.getHandleChineseChars()
+    }
+
+    public var stripAccents: Bool? {
+        self.bertNormalizer.getStripAccents()
+    }
+
+    public var lowercase: Bool {
+        self.bertNormalizer.getLowercase()
+    }
+}
+
+/// Precompiled normalizer
+///
+/// Reproduces the normalization of a SentencePiece model from its precompiled charsmap,
+/// as found in the `normalizer_spec` of a SentencePiece model.
+public class Precompiled: Normalizer {
+    /// - Parameters:
+    ///     - precompiledCharsmap:
+    ///         The raw bytes of the SentencePiece precompiled charsmap
+    public convenience init(precompiledCharsmap: [UInt8]) throws {
+        self.init(precompiled: try RustPrecompiled(precompiledCharsmap: precompiledCharsmap))
+    }
+
+    init(precompiled: RustPrecompiled) {
+        super.init(normalizer: precompiled.asNormalizer())
+    }
+}
+
+/// NFC Unicode Normalizer
+public class NFC: Normalizer {
+    public convenience init() {
+        self.init(nfc: RustNfc())
+    }
+
+    init(nfc: RustNfc) {
+        super.init(normalizer: nfc.asNormalizer())
+    }
+}
+
+/// NFD Unicode Normalizer
+public class NFD: Normalizer {
+    public convenience init() {
+        self.init(nfd: RustNfd())
+    }
+
+    init(nfd: RustNfd) {
+        super.init(normalizer: nfd.asNormalizer())
+    }
+}
+
+/// NFKC Unicode Normalizer
+public class NFKC: Normalizer {
+    public convenience init() {
+        self.init(nfkc: RustNfkc())
+    }
+
+    init(nfkc: RustNfkc) {
+        super.init(normalizer: nfkc.asNormalizer())
+    }
+}
+
+/// NFKD Unicode Normalizer
+public class NFKD: Normalizer {
+    public convenience init() {
+        self.init(nfkd: RustNfkd())
+    }
+
+    init(nfkd: RustNfkd) {
+        super.init(normalizer: nfkd.asNormalizer())
+    }
+}
+
+/// Lowercase Normalizer
+public class Lowercase: Normalizer {
+    public convenience init() {
+        self.init(lowercase: RustLowercase())
+    }
+
+    init(lowercase: RustLowercase) {
+        super.init(normalizer: lowercase.asNormalizer())
+    }
+}
+
+/// Strip normalizer
+///
+/// Removes the whitespaces on the left and/or right side of the input.
+public class Strip: Normalizer {
+    let strip: RustStrip
+
+    public convenience init(left: Bool = true, right: Bool = true) {
+        self.init(strip: RustStrip(left: left, right: right))
+    }
+
+    init(strip: RustStrip) {
+        self.strip = strip
+        super.init(normalizer: strip.asNormalizer())
+    }
+
+    public var left: Bool {
+        self.strip.getLeft()
+    }
+
+    public var right: Bool {
+        self.strip.getRight()
+    }
+}
+
+/// StripAccents normalizer
+///
+/// Removes the combining marks. It should be used after an ``NFD`` normalization.
+public class StripAccents: Normalizer {
+    public convenience init() {
+        self.init(stripAccents: RustStripAccents())
+    }
+
+    init(stripAccents: RustStripAccents) {
+        super.init(normalizer: stripAccents.asNormalizer())
+    }
+}
+
+/// Replace normalizer
+///
+/// Replaces every occurrence of the pattern with the given content.
+public class Replace: Normalizer {
+    /// - Parameters:
+    ///     - pattern:
+    ///         A string or a regular expression to replace
+    ///
+    ///     - content:
+    ///         The replacement content
+    public convenience init(_ pattern: Pattern, content: String) throws {
+        self.init(replace: try RustReplace(pattern: pattern.toRustPattern(), content: content))
+    }
+
+    init(replace: RustReplace) {
+        super.init(normalizer: replace.asNormalizer())
+    }
+}
+
+/// Prepend normalizer
+///
+/// Prepends the given string to the input, unless the input is empty.
+public class Prepend: Normalizer {
+    let prepend: RustPrepend
+
+    public convenience init(_ prepend: String) {
+        self.init(prepend: RustPrepend(prepend: prepend))
+    }
+
+    init(prepend: RustPrepend) {
+        self.prepend = prepend
+        super.init(normalizer: prepend.asNormalizer())
+    }
+
+    public var prependString: String {
+        self.prepend.getPrepend()
+    }
+}
+
+/// Allows concatenating multiple other ``Normalizer`` as a Sequence.
+/// All the normalizers run in sequence in the given order.
+public class NormalizerSequence: Normalizer {
+    public convenience init(_ normalizers: [Normalizer]) {
+        self.init(sequence: RustNormalizerSequence(normalizers: normalizers.map { $0.normalizer }))
+    }
+
+    init(sequence: RustNormalizerSequence) {
+        super.init(normalizer: sequence.asNormalizer())
+    }
+}
+
+//MARK:- Pre-Tokenizers
+
+/// The behavior to adopt when splitting a string on a delimiter.
+///
+/// With the delimiter `-` and the input `the-final--countdown`:
+///  - removed => `[ "the", "final", "countdown" ]`
+///  - isolated => `[ "the", "-", "final", "-", "-", "countdown" ]`
+///  - mergedWithPrevious => `[ "the-", "final-", "-", "countdown" ]`
+///  - mergedWithNext => `[ "the", "-final", "-", "-countdown" ]`
+///  - contiguous => `[ "the", "-", "final", "--", "countdown" ]`
+public typealias SplitDelimiterBehavior = RustSplitDelimiterBehavior
+
+/// Base class for all pre-tokenizers
+public class PreTokenizer {
+    let preTokenizer: RustPreTokenizer
+
+    init(preTokenizer: RustPreTokenizer) {
+        self.preTokenizer = preTokenizer
+    }
+
+    /// Wrap the given pre-tokenizer into the most specific subclass available.
+    static func wrap(_ preTokenizer: RustPreTokenizer) -> PreTokenizer {
+        if let byteLevel = preTokenizer.asByteLevel() {
+            return ByteLevel(byteLevel: byteLevel)
+        }
+        if let metaspace = preTokenizer.asMetaspace() {
+            return Metaspace(metaspace: metaspace)
+        }
+        if let whitespace = preTokenizer.asWhitespace() {
+            return Whitespace(whitespace: whitespace)
+        }
+        if let split = preTokenizer.asSplit() {
+            return Split(split: split)
+        }
+        if let punctuation = preTokenizer.asPunctuation() {
+            return Punctuation(punctuation: punctuation)
+        }
+        if let digits = preTokenizer.asDigits() {
+            return Digits(digits: digits)
+        }
+        if let delimiter = preTokenizer.asCharDelimiterSplit() {
+            return CharDelimiterSplit(charDelimiterSplit: delimiter)
+        }
+        if let whitespaceSplit = preTokenizer.asWhitespaceSplit() {
+            return WhitespaceSplit(whitespaceSplit: whitespaceSplit)
+        }
+        if let bert = preTokenizer.asBertPreTokenizer() {
+            return BertPreTokenizer(bertPreTokenizer: bert)
+        }
+        if let unicodeScripts = preTokenizer.asUnicodeScripts() {
+            return UnicodeScripts(unicodeScripts: unicodeScripts)
+        }
+        if let sequence = preTokenizer.asSequence() {
+            return PreTokenizerSequence(sequence: sequence)
+        }
+        return PreTokenizer(preTokenizer: preTokenizer)
+    }
+
+    /// Pre-tokenize the given string
+    ///
+    /// This method provides a way to visualize the effect of a ``PreTokenizer`` but it does
+    /// not keep track of the alignment, nor does it provide all the capabilities of the
+    /// underlying pre-tokenized string.
+    ///
+    /// - Parameters:
+    ///     - sequence:
+    ///         A string to pre-tokenize
+    ///
+    /// - Returns:
+    ///     A list of tuple with the pre-tokenized parts and their offsets
+    public func preTokenize(_ sequence: String) throws -> [(String, Offsets)] {
+        try self.preTokenizer.preTokenizeStr(s: sequence).map {
+            ($0.content, (start: Int($0.offsets[0]), end: Int($0.offsets[1])))
+        }
+    }
+}
+
+/// This pre-tokenizer simply splits using the following regex: `\w+|[^\w\s]+`
+public class Whitespace: PreTokenizer {
+    let whitespace: RustWhitespace
+
+    public convenience init() {
+        self.init(whitespace: RustWhitespace())
+    }
+
+    init(whitespace: RustWhitespace) {
+        self.whitespace = whitespace
+        super.init(preTokenizer: whitespace.asPreTokenizer())
+    }
+}
+
+/// ByteLevel pre-tokenizer
+///
+/// This pre-tokenizer takes care of replacing all bytes of the given string
+/// with a corresponding representation, as well as splitting into words.
+public class ByteLevel: PreTokenizer {
+    let byteLevel: RustByteLevel
+
+    /// - Parameters:
+    ///     - addPrefixSpace:
+    ///         Whether to add a space to the first word if there isn't already one. This
+    ///         lets us treat *hello* exactly like *say hello*.
+    ///     - trimOffsets:
+    ///         Whether the offsets should be trimmed to avoid including whitespaces.
+    ///     - useRegex:
+    ///         Set this to `false` to prevent this pre-tokenizer from using the GPT2 specific
+    ///         regexp for splitting on whitespace.
+    public convenience init(
+        addPrefixSpace: Bool = true, trimOffsets: Bool = true, useRegex: Bool = true
+    ) {
+        self.init(
+            byteLevel: RustByteLevel(
+                addPrefixSpace: addPrefixSpace, trimOffsets: trimOffsets, useRegex: useRegex))
+    }
+
+    init(byteLevel: RustByteLevel) {
+        self.byteLevel = byteLevel
+        super.init(preTokenizer: byteLevel.asPreTokenizer())
+    }
+
+    public var addPrefixSpace: Bool {
+        self.byteLevel.getAddPrefixSpace()
+    }
+
+    public var trimOffsets: Bool {
+        self.byteLevel.getTrimOffsets()
+    }
+
+    public var useRegex: Bool {
+        self.byteLevel.getUseRegex()
+    }
+
+    /// Returns the alphabet used by this pre-tokenizer.
+    ///
+    /// Since the ByteLevel works as its name suggests, at the byte level, it encodes each
+    /// byte value to a unique visible character. This means that there is a total of 256
+    /// different characters composing this alphabet.
+    ///
+    /// - Returns:
+    ///     A list of characters that compose the alphabet
+    public static func alphabet() -> [String] {
+        preTokenizersByteLevelAlphabet()
+    }
+}
+
+/// Metaspace pre-tokenizer
+///
+/// This pre-tokenizer replaces any whitespace by the provided replacement character.
+/// It then tries to split on these spaces.
+public class Metaspace: PreTokenizer {
+    let metaspace: RustMetaspace
+
+    /// - Parameters:
+    ///     - replacement:
+    ///         The replacement character. Must be exactly one character. By default we
+    ///         use the `▁` (U+2581) meta symbol (Same as in SentencePiece).
+    ///     - addPrefixSpace:
+    ///         Whether to add a space to the first word if there isn't already one. This
+    ///         lets us treat *hello* exactly like *say hello*.
+    public convenience init(replacement: String = "▁", addPrefixSpace: Bool = true) throws {
+        self.init(
+            metaspace: try RustMetaspace(replacement: replacement, addPrefixSpace: addPrefixSpace))
+    }
+
+    init(metaspace: RustMetaspace) {
+        self.metaspace = metaspace
+        super.init(preTokenizer: metaspace.asPreTokenizer())
+    }
+
+    public var replacement: String {
+        self.metaspace.getReplacement()
+    }
+
+    public var addPrefixSpace: Bool {
+        self.metaspace.getAddPrefixSpace()
+    }
+}
+
+/// Split pre-tokenizer
+///
+/// This versatile pre-tokenizer splits using the provided pattern and according to the
+/// provided behavior. The pattern can be inverted by making use of the invert flag.
+public class Split: PreTokenizer {
+    let split: RustSplit
+
+    /// - Parameters:
+    ///     - pattern:
+    ///         A pattern used to split the string. Usually a string or a regex.
+    ///     - behavior:
+    ///         The behavior to use when splitting.
+    ///     - invert:
+    ///         Whether to invert the pattern.
+    public convenience init(
+        _ pattern: Pattern, behavior: SplitDelimiterBehavior, invert: Bool = false
+    ) throws {
+        self.init(
+            split: try RustSplit(
+                pattern: pattern.toRustPattern(), behavior: behavior, invert: invert))
+    }
+
+    init(split: RustSplit) {
+        self.split = split
+        super.init(preTokenizer: split.asPreTokenizer())
+    }
+
+    public var pattern: Pattern {
+        Pattern(self.split.getPattern())
+    }
+
+    public var behavior: SplitDelimiterBehavior {
+        self.split.getBehavior()
+    }
+
+    public var invert: Bool {
+        self.split.getInvert()
+    }
+}
+
+/// This pre-tokenizer simply splits on punctuation as individual characters.
+public class Punctuation: PreTokenizer {
+    let punctuation: RustPunctuation
+
+    /// - Parameters:
+    ///     - behavior:
+    ///         The behavior to use when splitting.
+    public convenience init(behavior: SplitDelimiterBehavior = .isolated) {
+        self.init(punctuation: RustPunctuation(behavior: behavior))
+    }
+
+    init(punctuation: RustPunctuation) {
+        self.punctuation = punctuation
+        super.init(preTokenizer: punctuation.asPreTokenizer())
+    }
+
+    public var behavior: SplitDelimiterBehavior {
+        self.punctuation.getBehavior()
+    }
+}
+
+/// This pre-tokenizer simply splits using the digits in separate tokens
+public class Digits: PreTokenizer {
+    let digits: RustDigits
+
+    /// - Parameters:
+    ///     - individualDigits:
+    ///         If set to true, digits will each be separated as follows:
+    ///         "Call 123 please" -> "Call ", "1", "2", "3", " please".
+    ///         If set to false, digits will grouped as follows:
+    ///         "Call 123 please" -> "Call ", "123", " please".
+    public convenience init(individualDigits: Bool = false) {
+        self.init(digits: RustDigits(individualDigits: individualDigits))
+    }
+
+    init(digits: RustDigits) {
+        self.digits = digits
+        super.init(preTokenizer: digits.asPreTokenizer())
+    }
+
+    public var individualDigits: Bool {
+        self.digits.getIndividualDigits()
+    }
+}
+
+/// This pre-tokenizer simply splits on the provided char. Works like `.split(delimiter)`
+public class CharDelimiterSplit: PreTokenizer {
+    let charDelimiterSplit: RustCharDelimiterSplit
+
+    /// - Parameters:
+    ///     - delimiter:
+    ///         The delimiter char that will be used to split input. Must be exactly one
+    ///         character.
+    public convenience init(_ delimiter: String) throws {
+        self.init(charDelimiterSplit: try RustCharDelimiterSplit(delimiter: delimiter))
+    }
+
+    init(charDelimiterSplit: RustCharDelimiterSplit) {
+        self.charDelimiterSplit = charDelimiterSplit
+        super.init(preTokenizer: charDelimiterSplit.asPreTokenizer())
+    }
+
+    public var delimiter: String {
+        self.charDelimiterSplit.getDelimiter()
+    }
+}
+
+/// This pre-tokenizer simply splits on the whitespace. Works like `.split()`
+public class WhitespaceSplit: PreTokenizer {
+    public convenience init() {
+        self.init(whitespaceSplit: RustWhitespaceSplit())
+    }
+
+    init(whitespaceSplit: RustWhitespaceSplit) {
+        super.init(preTokenizer: whitespaceSplit.asPreTokenizer())
+    }
+}
+
+/// BertPreTokenizer
+///
+/// This pre-tokenizer splits tokens on spaces, and also on punctuation.
+/// Each occurence of a punctuation character will be treated separately.
+public class BertPreTokenizer: PreTokenizer {
+    public convenience init() {
+        self.init(bertPreTokenizer: RustBertPreTokenizer())
+    }
+
+    init(bertPreTokenizer: RustBertPreTokenizer) {
+        super.init(preTokenizer: bertPreTokenizer.asPreTokenizer())
+    }
+}
+
+/// This pre-tokenizer splits on characters that belong to different language family
+///
+/// It roughly follows https://github.com/google/sentencepiece/blob/master/data/Scripts.txt
+/// Actually Hiragana and Katakana are fused with Han, and 0x30FC is Han too.
+/// This mimicks SentencePiece Unigram implementation.
+public class UnicodeScripts: PreTokenizer {
+    public convenience init() {
+        self.init(unicodeScripts: RustUnicodeScripts())
+    }
+
+    init(unicodeScripts: RustUnicodeScripts) {
+        super.init(preTokenizer: unicodeScripts.asPreTokenizer())
+    }
+}
+
+/// This pre-tokenizer composes other pre-tokenizers and applies them in sequence
+public class PreTokenizerSequence: PreTokenizer {
+    let sequence: RustPreTokenizerSequence
+
+    public convenience init(_ preTokenizers: [PreTokenizer]) {
+        self.init(
+            sequence: RustPreTokenizerSequence(preTokenizers: preTokenizers.map { $0.preTokenizer }))
+    }
+
+    init(sequence: RustPreTokenizerSequence) {
+        self.sequence = sequence
+        super.init(preTokenizer: sequence.asPreTokenizer())
+    }
+
+    /// The pre-tokenizers composing this sequence.
+    ///
+    /// They are copies: modifying them doesn't affect this sequence.
+    public var preTokenizers: [PreTokenizer] {
+        self.sequence.getPreTokenizers().map { PreTokenizer.wrap($0) }
+    }
+}
+
+//MARK:- Decoders
+
+/// Base class for all decoders
+public class Decoder {
+    let decoder: RustDecoder
+
+    init(decoder: RustDecoder) {
+        self.decoder = decoder
+    }
+
+    /// Wrap the given decoder into the most specific subclass available.
+    static func wrap(_ decoder: RustDecoder) -> Decoder {
+        if let byteLevel = decoder.asByteLevel() {
+            return ByteLevelDecoder(byteLevel: byteLevel)
+        }
+        if let metaspace = decoder.asMetaspace() {
+            return MetaspaceDecoder(metaspace: metaspace)
+        }
+        if let wordPiece = decoder.asWordpiece() {
+            return WordPieceDecoder(wordPiece: wordPiece)
+        }
+        if let bpe = decoder.asBpe() {
+            return BPEDecoder(bpe: bpe)
+        }
+        if let ctc = decoder.asCtc() {
+            return CTC(ctc: ctc)
+        }
+        if let replace = decoder.asReplace() {
+            return ReplaceDecoder(replace: replace)
+        }
+        if let fuse = decoder.asFuse() {
+            return Fuse(fuse: fuse)
+        }
+        if let strip = decoder.asStrip() {
+            return StripDecoder(strip: strip)
+        }
+        if let byteFallback = decoder.asByteFallback() {
+            return ByteFallback(byteFallback: byteFallback)
+        }
+        if let sequence = decoder.asSequence() {
+            return DecoderSequence(sequence: sequence)
+        }
+        return Decoder(decoder: decoder)
+    }
+
+    /// Decode the given list of tokens to a final string
+    ///
+    /// - Parameters:
+    ///     - tokens:
+    ///         The list of tokens to decode
+    ///
+    /// - Returns:
+    ///     The decoded string
+    public func decode(_ tokens: [String]) throws -> String {
+        try self.decoder.decodeTokens(tokens: tokens)
+    }
+}
+
+/// ByteLevel decoder
+///
+/// This decoder is to be used in tandem with the ``ByteLevel`` pre-tokenizer.
+public class ByteLevelDecoder: Decoder {
+    public convenience init() {
+        self.init(byteLevel: RustByteLevelDecoder())
+    }
+
+    init(byteLevel: RustByteLevelDecoder) {
+        super.init(decoder: byteLevel.asDecoder())
+    }
+}
+
+/// Metaspace decoder
+///
+/// This decoder is to be used in tandem with the ``Metaspace`` pre-tokenizer.
+public class MetaspaceDecoder: Decoder {
+    let metaspace: RustMetaspaceDecoder
+
+    /// - Parameters:
+    ///     - replacement:
+    ///         The replacement character. Must be exactly one character. By default we
+    ///         use the `▁` (U+2581) meta symbol (Same as in SentencePiece).
+    ///     - addPrefixSpace:
+    ///         Whether to add a space to the first word if there isn't already one. This
+    ///         lets us treat *hello* exactly like *say hello*.
+    public convenience init(replacement: String = "▁", addPrefixSpace: Bool = true) throws {
+        self.init(
+            metaspace: try RustMetaspaceDecoder(
+                replacement: replacement, addPrefixSpace: addPrefixSpace))
+    }
+
+    init(metaspace: RustMetaspaceDecoder) {
+        self.metaspace = metaspace
+        super.init(decoder: metaspace.asDecoder())
+    }
+
+    public var replacement: String {
+        self.metaspace.getReplacement()
+    }
+
+    public var addPrefixSpace: Bool {
+        self.metaspace.getAddPrefixSpace()
+    }
+}
+
+/// WordPiece decoder
+///
+/// This decoder is to be used in tandem with the ``WordPiece`` model.
+public class WordPieceDecoder: Decoder {
+    let wordPiece: RustWordPieceDecoder
+
+    /// - Parameters:
+    ///     - prefix:
+    ///         The prefix to use for subwords that are not a beginning-of-word
+    ///     - cleanup:
+    ///         Whether to cleanup some tokenization artifacts. Mainly spaces before punctuation,
+    ///         and some abbreviated english forms.
+    public convenience init(prefix: String = "##", cleanup: Bool = true) {
+        self.init(wordPiece: RustWordPieceDecoder(prefix: prefix, cleanup: cleanup))
+    }
+
+    init(wordPiece: RustWordPieceDecoder) {
+        self.wordPiece = wordPiece
+        super.init(decoder: wordPiece.asDecoder())
+    }
+
+    public var prefix: String {
+        self.wordPiece.getPrefix()
+    }
+
+    public var cleanup: Bool {
+        self.wordPiece.getCleanup()
+    }
+}
+
+/// BPE decoder
+///
+/// This decoder is to be used in tandem with the ``BPE`` model.
+public class BPEDecoder: Decoder {
+    let bpe: RustBpeDecoder
+
+    /// - Parameters:
+    ///     - suffix:
+    ///         The suffix that was used to caracterize an end-of-word. This suffix will
+    ///         be replaced by whitespaces during the decoding
+    public convenience init(suffix: String = "</w>") {
+        self.init(bpe: RustBpeDecoder(suffix: suffix))
+    }
+
+    init(bpe: RustBpeDecoder) {
+        self.bpe = bpe
+        super.init(decoder: bpe.asDecoder())
+    }
+
+    public var suffix: String {
+        self.bpe.getSuffix()
+    }
+}
+
+/// CTC decoder
+///
+/// This decoder is to be used in tandem with CTC models (e.g. Wav2Vec2).
+public class CTC: Decoder {
+    let ctc: RustCtc
+
+    /// - Parameters:
+    ///     - padToken:
+    ///         The pad token used by CTC to delimit a new token.
+    ///     - wordDelimiterToken:
+    ///         The word delimiter token. It will be replaced by a space.
+    ///     - cleanup:
+    ///         Whether to cleanup some tokenization artifacts. Mainly spaces before punctuation,
+    ///         and some abbreviated english forms.
+    public convenience init(
+        padToken: String = "<pad>", wordDelimiterToken: String = "|", cleanup: Bool = true
+    ) {
+        self.init(
+            ctc: RustCtc(padToken: padToken, wordDelimiterToken: wordDelimiterToken, cleanup: cleanup))
+    }
+
+    init(ctc: RustCtc) {
+        self.ctc = ctc
+        super.init(decoder: ctc.asDecoder())
+    }
+
+    public var padToken: String {
+        self.ctc.getPadToken()
+    }
+
+    public var wordDelimiterToken: String {
+        self.ctc.getWordDelimiterToken()
+    }
+
+    public var cleanup: Bool {
+        self.ctc.getCleanup()
+    }
+}
+
+/// Replace decoder
+///
+/// Replaces every occurrence of the pattern in each token with the given content.
+public class ReplaceDecoder: Decoder {
+    /// - Parameters:
+    ///     - pattern:
+    ///         A string or a regular expression to replace
+    ///
+    ///     - content:
+    ///         The replacement content
+    public convenience init(_ pattern: Pattern, content: String) throws {
+        self.init(
+            replace: try RustReplaceDecoder(pattern: pattern.toRustPattern(), content: content))
+    }
+
+    init(replace: RustReplaceDecoder) {
+        super.init(decoder: replace.asDecoder())
+    }
+}
+
+/// Fuse decoder
+///
+/// Fuse simply fuses every token into a single string. This is usually the last decoding
+/// step anyway, but this decoder exists in case some decoders need to happen after that step.
+public class Fuse: Decoder {
+    public convenience init() {
+        self.init(fuse: RustFuse())
+    }
+
+    init(fuse: RustFuse) {
+        super.init(decoder: fuse.asDecoder())
+    }
+}
+
+/// Strip decoder
+///
+/// Strips `start` occurrences of the content from the left of each token, and `stop`
+/// occurrences from its right.
+public class StripDecoder: Decoder {
+    let strip: RustStripDecoder
+
+    /// - Parameters:
+    ///     - content:
+    ///         The character to strip. Must be exactly one character.
+    ///     - start:
+    ///         The number of characters to strip from the left of each token
+    ///     - stop:
+    ///         The number of characters to strip from the right of each token
+    public convenience init(content: String = " ", start: Int = 0, stop: Int = 0) throws {
+        self.init(
+            strip: try RustStripDecoder(
+                content: content, start: UInt64(start), stop: UInt64(stop)))
+    }
+
+    init(strip: RustStripDecoder) {
+        self.strip = strip
+        super.init(decoder: strip.asDecoder())
+    }
+
+    public var content: String {
+        self.strip.getContent()
+    }
+
+    public var start: Int {
+        Int(self.strip.getStart())
+    }
+
+    public var stop: Int {
+        Int(self.strip.getStop())
+    }
+}
+
+/// ByteFallback decoder
+///
+/// ByteFallback is a simple trick which converts tokens looking like `<0x61>` to pure bytes,
+/// and attempts to make them into a string. If the tokens cannot be decoded you will get
+/// `�` instead for each inconvertable byte token.
+public class ByteFallback: Decoder {
+    public convenience init() {
+        self.init(byteFallback: RustByteFallback())
+    }
+
+    init(byteFallback: RustByteFallback) {
+        super.init(decoder: byteFallback.asDecoder())
+    }
+}
+
+/// Allows concatenating multiple other ``Decoder`` as a Sequence.
+/// All the decoders run in sequence in the given order.
+public class DecoderSequence: Decoder {
+    let sequence: RustDecoderSequence
+
+    public convenience init(_ decoders: [Decoder]) {
+        self.init(sequence: RustDecoderSequence(decoders: decoders.map { $0.decoder }))
+    }
+
+    init(sequence: RustDecoderSequence) {
+        self.sequence = sequence
+        super.init(decoder: sequence.asDecoder())
+    }
+
+    /// The decoders composing this sequence.
+    ///
+    /// They are copies: modifying them doesn't affect this sequence.
+    public var decoders: [Decoder] {
+        self.sequence.getDecoders().map { Decoder.wrap($0) }
+    }
+}
+
+//MARK:- Post-Processors
+
+/// Base class for all post-processors
+public class PostProcessor {
+    let postProcessor: RustPostProcessor
+
+    init(postProcessor: RustPostProcessor) {
+        self.postProcessor = postProcessor
+    }
+
+    /// Wrap the given post-processor into the most specific subclass available.
+    static func wrap(_ postProcessor: RustPostProcessor) -> PostProcessor {
+        if let byteLevel = postProcessor.asByteLevel() {
+            return ByteLevelProcessing(byteLevel: byteLevel)
+        }
+        if let template = postProcessor.asTemplate() {
+            return TemplateProcessing(template: template)
+        }
+        if let bert = postProcessor.asBert() {
+            return BertProcessing(bert: bert)
+        }
+        if let roberta = postProcessor.asRoberta() {
+            return RobertaProcessing(roberta: roberta)
+        }
+        if let sequence = postProcessor.asSequence() {
+            return PostProcessorSequence(sequence: sequence)
+        }
+        return PostProcessor(postProcessor: postProcessor)
+    }
+
+    /// Return the number of special tokens that would be added for single/pair sentences.
+    ///
+    /// - Parameters:
+    ///     - isPair:
+    ///         Whether the input would be a pair of sequences
+    ///
+    /// - Returns:
+    ///     The number of tokens to add
+    public func numSpecialTokensToAdd(isPair: Bool) -> Int {
+        Int(self.postProcessor.numSpecialTokensToAdd(isPair: isPair))
+    }
+}
+
+/// This post-processor takes care of trimming the offsets.
+///
+/// By default, the ByteLevel BPE might include whitespaces in the produced tokens. If you
+/// don't want the offsets to include these whitespaces, then this post-processor must be used.
+public class ByteLevelProcessing: PostProcessor {
+    let byteLevel: RustByteLevelProcessor
+
+    /// - Parameters:
+    ///     - trimOffsets:
+    ///         Whether to trim the whitespaces from the produced offsets.
+    public convenience init(trimOffsets: Bool = true) {
+        self.init(byteLevel: RustByteLevelProcessor(trimOffsets: trimOffsets))
+    }
+
+    init(byteLevel: RustByteLevelProcessor) {
+        self.byteLevel = byteLevel
+        super.init(postProcessor: byteLevel.asPostProcessor())
+    }
+
+    public var trimOffsets: Bool {
+        self.byteLevel.getTrimOffsets()
+    }
+}
+
+/// Provides a way to specify templates in order to add the special tokens to each
+/// input sequence as relevant.
+///
+/// Let's take `BERT` tokenizer as an example. It uses two special tokens, used to
+/// delimitate each sequence. `[CLS]` is always used at the beginning of the first
+/// sequence, and `[SEP]` is added at the end of both the first, and the pair
+/// sequences. The final result looks like this:
+///
+/// - Single sequence: `[CLS] Hello there [SEP]`
+/// - Pair sequences: `[CLS] My name is Anthony [SEP] What is my name? [SEP]`
+///
+/// You can achieve such behavior using a TemplateProcessing:
+///
+/// ```
+/// TemplateProcessing(
+///     single: "[CLS] $0 [SEP]",
+///     pair: "[CLS] $A [SEP] $B:1 [SEP]:1",
+///     specialTokens: [("[CLS]", 1), ("[SEP]", 0)])
+/// ```
+///
+/// Each input sequence is identified using a `$` construct, which lets us specify each
+/// input sequence and the type_id to use:
+///
+/// - Specifying the sequence, with default `type_id == 0`: `$A` or `$B`
+/// - Specifying the `type_id` with default `sequence == A`: `$0`, `$1`, `$2`, ...
+/// - Specifying both: `$A:0`, `$B:1`, ...
+///
+/// The same construct is used for special tokens: `<identifier>(:<type_id>)?`.
+public class TemplateProcessing: PostProcessor {
+    /// - Parameters:
+    ///     - single:
+    ///         The template used for single sequences
+    ///     - pair:
+    ///         The template used when both sequences are specified
+    ///     - specialTokens:
+    ///         The list of special tokens used in each sequences, with their ids
+    public convenience init(
+        single: String? = nil, pair: String? = nil, specialTokens: [(String, UInt32)] = []
+    ) throws {
+        let specialTokens = specialTokens.map {
+            RustSpecialToken(id: $0.0, ids: [$0.1], tokens: [$0.0])
+        }
+        self.init(
+            template: try RustTemplateProcessing(
+                single: single, pair: pair, specialTokens: specialTokens))
+    }
+
+    init(template: RustTemplateProcessing) {
+        super.init(postProcessor: template.asPostProcessor())
+    }
+}
+
+/// This post-processor takes care of adding the special tokens needed by
+/// a Bert model:
+///
+/// - a SEP token
+/// - a CLS token
+public class BertProcessing: PostProcessor {
+    let bert: RustBertProcessing
+
+    /// - Parameters:
+    ///     - sep:
+    ///         A tuple with the string representation of the SEP token, and its id
+    ///     - cls:
+    ///         A tuple with the string representation of the CLS token, and its id
+    public convenience init(sep: (String, UInt32), cls: (String, UInt32)) {
+        self.init(
+            bert: RustBertProcessing(
+                sep: RustTokenId(token: sep.0, id: sep.1),
+                cls: RustTokenId(token: cls.0, id: cls.1)))
+    }
+
+    init(bert: RustBertProcessing) {
+        self.bert = bert
+        super.init(postProcessor: bert.asPostProcessor())
+    }
+
+    public var sep: (String, UInt32) {
+        let sep = self.bert.getSep()
+        return (sep.token, sep.id)
+    }
+
+    public var cls: (String, UInt32) {
+        let cls = self.bert.getCls()
+        return (cls.token, cls.id)
+    }
+}
+
+/// This post-processor takes care of adding the special tokens needed by
+/// a Roberta model:
+///
+/// - a SEP token
+/// - a CLS token
+///
+/// It also takes care of trimming the offsets.
+/// By default, the ByteLevel BPE might include whitespaces in the produced tokens. If you
+/// don't want the offsets to include these whitespaces, then this post-processor should be
+/// initialized with `trimOffsets: true`.
+public class RobertaProcessing: PostProcessor {
+    let roberta: RustRobertaProcessing
+
+    /// - Parameters:
+    ///     - sep:
+    ///         A tuple with the string representation of the SEP token, and its id
+    ///     - cls:
+    ///         A tuple with the string representation of the CLS token, and its id
+    ///     - trimOffsets:
+    ///         Whether to trim the whitespaces from the produced offsets.
+    ///     - addPrefixSpace:
+    ///         Whether the addPrefixSpace option was enabled during pre-tokenization. This
+    ///         is relevant because it defines the way the offsets are trimmed out.
+    public convenience init(
+        sep: (String, UInt32), cls: (String, UInt32), trimOffsets: Bool = true,
+        addPrefixSpace: Bool = true
+    ) {
+        self.init(
+            roberta: RustRobertaProcessing(
+                sep: RustTokenId(token: sep.0, id: sep.1),
+                cls: RustTokenId(token: cls.0, id: cls.1),
+                trimOffsets: trimOffsets, addPrefixSpace: addPrefixSpace))
+    }
+
+    init(roberta: RustRobertaProcessing) {
+        self.roberta = roberta
+        super.init(postProcessor: roberta.asPostProcessor())
+    }
+
+    public var sep: (String, UInt32) {
+        let sep = self.roberta.getSep()
+        return (sep.token, sep.id)
+    }
+
+    public var cls: (String, UInt32) {
+        let cls = self.roberta.getCls()
+        return (cls.token, cls.id)
+    }
+
+    public var trimOffsets: Bool {
+        self.roberta.getTrimOffsets()
+    }
+
+    public var addPrefixSpace: Bool {
+        self.roberta.getAddPrefixSpace()
+    }
+}
+
+/// Allows concatenating multiple other ``PostProcessor`` as a Sequence.
+/// All the post-processors run in sequence in the given order.
+public class PostProcessorSequence: PostProcessor {
+    let sequence: RustPostProcessorSequence
+
+    public convenience init(_ processors: [PostProcessor]) {
+        self.init(
+            sequence: RustPostProcessorSequence(processors: processors.map { $0.postProcessor }))
+    }
+
+    init(sequence: RustPostProcessorSequence) {
+        self.sequence = sequence
+        super.init(postProcessor: sequence.asPostProcessor())
+    }
+
+    /// The post-processors composing this sequence.
+    ///
+    /// They are copies: modifying them doesn't affect this sequence.
+    public var processors: [PostProcessor] {
+        self.sequence.getProcessors().map { PostProcessor.wrap($0) }
+    }
+}
+
+//MARK:- Chat Templates
+
+/// A message of a conversation, e.g. `ChatMessage(role: "user", content: "Hello!")`.
+public struct ChatMessage {
+    public var role: String
+    public var content: String
+
+    public init(role: String, content: String) {
+        self.role = role
+        self.content = content
+    }
+
+    func toRustChatMessage() -> RustChatMessage {
+        RustChatMessage(role: self.role, content: self.content)
+    }
+}
+
+/// A conversation encoded for supervised fine-tuning, where the loss only covers the
+/// assistant messages.
+///
+/// See ``ChatTemplate/encodeWithAssistantMasks(_:with:)``.
+public struct ChatEncoding {
+    public let encoding: Encoding
+    /// 1 for the tokens of the assistant messages, and 0 elsewhere.
+    public let assistantMasks: [UInt32]
+    /// The ids of the assistant tokens, and -100, which the loss ignores, elsewhere.
+    public let labels: [Int64]
+
+    init(_ encoding: RustChatEncoding) {
+        self.encoding = Encoding(encoding.encoding)
+        self.assistantMasks = encoding.assistantMasks
+        self.labels = encoding.labels
+    }
+}
+
+/// Formats a conversation into the prompt of a chat model, with the Jinja template found in
+/// the `tokenizer_config.json` file of the model.
+///
+/// The templates are rendered with MiniJinja, in the environment of `transformers`:
+/// `trim_blocks` and `lstrip_blocks` are set, the common `str` and `dict` methods are
+/// available, and so are `raise_exception()`, whose message is thrown as an error,
+/// `strftime_now()` and the `{% generation %}` blocks.
+public class ChatTemplate {
+    let template: RustChatTemplate
+
+    /// Parse a chat template
+    ///
+    /// - Parameters:
+    ///     - template:
+    ///         The Jinja source of the template
+    ///     - bosToken:
+    ///         The value of `bos_token` in the template
+    ///     - eosToken:
+    ///         The value of `eos_token` in the template
+    public init(_ template: String, bosToken: String? = nil, eosToken: String? = nil) throws {
+        self.template = try RustChatTemplate(
+            template: template, bosToken: bosToken, eosToken: eosToken)
+    }
+
+    /// Load the `chat_template` of a `tokenizer_config.json` file, along with its
+    /// `bos_token` and `eos_token`. When the file has several named templates, the
+    /// `default` one is used.
+    ///
+    /// - Parameters:
+    ///     - path:
+    ///         A path to a local `tokenizer_config.json` file
+    public init(contentsOfFile path: String) throws {
+        self.template = try RustChatTemplate.fromFile(path: path)
+    }
+
+    public var bosToken: String? {
+        self.template.getBosToken()
+    }
+
+    public var eosToken: String? {
+        self.template.getEosToken()
+    }
+
+    /// Render the given conversation into a prompt
+    ///
+    /// - Parameters:
+    ///     - messages:
+    ///         The messages of the conversation
+    ///     - addGenerationPrompt:
+    ///         Whether to end the prompt with the header of an assistant message, for the
+    ///         model to generate it
+    ///
+    /// - Returns:
+    ///     The prompt
+    public func render(_ messages: [ChatMessage], addGenerationPrompt: Bool = false) throws
+        -> String
+    {
+        try self.template.render(
+            messages: messages.map { $0.toRustChatMessage() },
+            addGenerationPrompt: addGenerationPrompt)
+    }
+
+    /// Render the given conversation and encode the prompt. The special tokens come from the
+    /// template, so the tokenizer doesn't add any.
+    ///
+    /// - Parameters:
+    ///     - messages:
+    ///         The messages of the conversation
+    ///     - tokenizer:
+    ///         The tokenizer of the model
+    ///     - addGenerationPrompt:
+    ///         Whether to end the prompt with the header of an assistant message
+    ///
+    /// - Returns:
+    ///     The encoded prompt
+    public func encode(
+        _ messages: [ChatMessage], with tokenizer: Tokenizer, addGenerationPrompt: Bool = false
+    ) throws -> Encoding {
+        Encoding(
+            try self.template.encode(
+                tokenizer: tokenizer.tokenizer,
+                messages: messages.map { $0.toRustChatMessage() },
+                addGenerationPrompt: addGenerationPrompt))
+    }
+
+    /// Encode the given conversation like ``encode(_:with:addGenerationPrompt:)``, and mark
+    /// the tokens of the assistant messages, to train on them only.
+    ///
+    /// When the template has `{% generation %}` blocks, the tokens of their content are the
+    /// ones marked. Otherwise, an assistant message spans the text the template adds after
+    /// the prompt of the previous messages, so its end-of-turn tokens are trained on too, and
+    /// the template has to render each message after the previous ones, without changing
+    /// them.
+    ///
+    /// - Parameters:
+    ///     - messages:
+    ///         The messages of the conversation
+    ///     - tokenizer:
+    ///         The tokenizer of the model
+    ///
+    /// - Returns:
+    ///     The encoded conversation, with its masks and labels
+    public func encodeWithAssistantMasks(_ messages: [ChatMessage], with tokenizer: Tokenizer)
+        throws -> ChatEncoding
+    {
+        ChatEncoding(
+            try self.template.encodeWithAssistantMasks(
+                tokenizer: tokenizer.tokenizer,
+                messages: messages.map { $0.toRustChatMessage() }))
     }
 }
@@ -11,6 +11,300 @@ final class TokenizerTests: XCTestCase {
         XCTAssertNotNil(tokenizer.model)
     }
 
+    func testModelAndPreTokenizerFromFile() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+
+        XCTAssert(tokenizer.model is BPE)
+        XCTAssert(tokenizer.preTokenizer is Whitespace)
+    }
+
+    func testWordPieceModel() throws {
+        let vocab: Vocab = ["[UNK]": 0, "hello": 1, "##world": 2]
+        let tokenizer = Tokenizer(model: try WordPiece(vocab: vocab, unkToken: "[UNK]"))
+        tokenizer.preTokenizer = Whitespace()
+
+        let output = try tokenizer.encode("helloworld hi")
+        XCTAssertEqual(output.tokens, ["hello", "##world", "[UNK]"])
+        XCTAssert(tokenizer.model is WordPiece)
+    }
+
+    func testUnigramNBest() throws {
+        let model = try Unigram(
+            vocab: [
+                ("<unk>", 0.0), ("a", 0.0), ("b", 0.0), ("c", 0.0), ("d", 0.0),
+                ("cd", 1.0), ("ab", 2.0), ("abc", 5.0), ("abcd", 10.0),
+            ],
+            unkId: 0)
+
+        XCTAssertEqual(model.score(of: "abc"), 5.0)
+
+        let segmentations = try model.nbest("abcdacd", n: 2)
+        XCTAssertEqual(segmentations.count, 2)
+        XCTAssertEqual(segmentations[0].tokens, ["abcd", "a", "cd"])
+        XCTAssertEqual(segmentations[0].logProb, 11.0)
+        XCTAssertEqual(segmentations[1].tokens, ["abcd", "a", "c", "d"])
+
+        let unknown = try model.nbest("axy", n: 1)
+        XCTAssertEqual(unknown[0].tokens, ["a", "<unk>"])
+        XCTAssertEqual(unknown[0].ids, [1, 0])
+
+        let byteFallback = try Unigram(
+            vocab: [("<unk>", 0.0), ("a", 0.0), ("<0x78>", 0.0), ("<0x79>", 0.0)],
+            unkId: 0, byteFallback: true)
+        XCTAssertEqual(try byteFallback.nbest("axy", n: 1)[0].tokens, ["a", "<0x78>", "<0x79>"])
+
+        let noUnk = try Unigram(vocab: [("a", 0.0)])
+        XCTAssertThrowsError(try noUnk.nbest("ab", n: 1))
+    }
+
+    func testTrainUnigram() throws {
+        let corpus = FileManager.default.temporaryDirectory.appendingPathComponent(
+            "unigram-corpus.txt")
+        let lines = Array(repeating: "今日は東京の天気です\n明日は大阪は雨です", count: 50)
+        try lines.joined(separator: "\n").write(to: corpus, atomically: true, encoding: .utf8)
+
+        let tokenizer = Tokenizer(model: try Unigram())
+        // A handle taken before training sees the trained vocab
+        let unigram = tokenizer.model as! Unigram
+        XCTAssertEqual(unigram.score(of: "<unk>"), 0.0)
+        let trainer = try UnigramTrainer(
+            vocabSize: 30, showProgress: false, specialTokens: ["<unk>"], unkToken: "<unk>",
+            seedSize: 1000)
+        XCTAssertEqual(trainer.seedSize, 1000)
+        try tokenizer.train(files: [corpus.path], trainer: trainer)
+
+        XCTAssert(tokenizer.model is Unigram)
+        XCTAssertEqual(try tokenizer.encode("今日").tokens.joined(), "今日")
+        for (piece, score) in unigram.vocab {
+            XCTAssertEqual(unigram.score(of: piece), score)
+        }
+        XCTAssertThrowsError(try UnigramTrainer(initialAlphabet: ["ab"]))
+    }
+
+    func testTrainWordLevel() throws {
+        let corpus = FileManager.default.temporaryDirectory.appendingPathComponent(
+            "word-level-corpus.txt")
+        try "the cat sat\nthe cat ran\nthe dog sat".write(
+            to: corpus, atomically: true, encoding: .utf8)
+
+        let tokenizer = Tokenizer(model: try WordLevel(unkToken: "[UNK]"))
+        tokenizer.preTokenizer = Whitespace()
+        let trainer = try WordLevelTrainer(
+            minFrequency: 2, showProgress: false, specialTokens: ["[UNK]"])
+        try tokenizer.train(files: [corpus.path], trainer: trainer)
+
+        XCTAssertEqual(
+            tokenizer.getVocab(includeAddedTokens: true),
+            ["[UNK]": 0, "the": 1, "cat": 2, "sat": 3])
+        XCTAssertEqual(try tokenizer.encode("the dog sat").tokens, ["the", "[UNK]", "sat"])
+    }
+
+    func testNormalizers() throws {
+        let normalizer = NormalizerSequence([
+            NFD(), StripAccents(), Lowercase(), Strip(), try Replace(.regex("\\s+"), content: " "),
+        ])
+        XCTAssertEqual(try normalizer.normalize("  Héllo   WÖRLD "), "hello world")
+
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+        tokenizer.normalizer = NormalizerSequence([NFD(), StripAccents()])
+
+        XCTAssert(tokenizer.normalizer is NormalizerSequence)
+        XCTAssertEqual(try tokenizer.encode("Héllo").tokens, ["Hello"])
+    }
+
+    func testBertNormalizer() throws {
+        let text = "Héllo\tWÖRLD\u{0}你好"
+
+        XCTAssertEqual(try BertNormalizer().normalize(text), "hello world 你  好 ")
+        XCTAssertEqual(
+            try BertNormalizer(cleanText: false).normalize(text), "hello\tworld\u{0} 你  好 ")
+        XCTAssertEqual(
+            try BertNormalizer(handleChineseChars: false).normalize(text), "hello world你好")
+        XCTAssertEqual(
+            try BertNormalizer(stripAccents: false).normalize(text), "héllo wörld 你  好 ")
+        XCTAssertEqual(
+            try BertNormalizer(lowercase: false).normalize(text), "Héllo WÖRLD 你  好 ")
+        XCTAssertEqual(
+            try BertNormalizer(stripAccents: true, lowercase: false).normalize(text),
+            "Hello WORLD 你  好 ")
+    }
+
+    func testPrecompiled() throws {
+        // The normalizer of a SentencePiece tokenizer.json, with its `nmt_nfkc` charsmap
+        let filePath = Bundle.module.path(
+            forResource: "precompiled-normalizer", ofType: "json", inDirectory: "Files")!
+        let json = try JSONSerialization.jsonObject(
+            with: try Data(contentsOf: URL(fileURLWithPath: filePath))) as! [String: Any]
+        let charsmap = Data(base64Encoded: json["precompiled_charsmap"] as! String)!
+
+        let precompiled = try Precompiled(precompiledCharsmap: [UInt8](charsmap))
+        XCTAssertEqual(try precompiled.normalize("ﬁne 𝔾𝕠"), "fine Go")
+        XCTAssertEqual(try precompiled.normalize("Ｈｅｌｌｏ　１２３"), "Hello 123")
+        XCTAssertEqual(try precompiled.normalize("a\u{200d}b"), "a b")
+
+        XCTAssertThrowsError(try Precompiled(precompiledCharsmap: [8, 0, 0, 0, 1, 2]))
+    }
+
+    func testByteLevel() throws {
+        let corpus = FileManager.default.temporaryDirectory.appendingPathComponent(
+            "byte-level-corpus.txt")
+        try "Hello, y'all! How are you?".write(to: corpus, atomically: true, encoding: .utf8)
+
+        let tokenizer = Tokenizer(model: try BPE())
+        tokenizer.preTokenizer = ByteLevel(addPrefixSpace: false)
+        tokenizer.decoder = ByteLevelDecoder()
+        tokenizer.postProcessor = ByteLevelProcessing()
+
+        let trainer = try BPETrainer(showProgress: false, initialAlphabet: ByteLevel.alphabet())
+        try tokenizer.train(files: [corpus.path], trainer: trainer)
+
+        XCTAssertEqual(ByteLevel.alphabet().count, 256)
+        XCTAssert(tokenizer.decoder is ByteLevelDecoder)
+        XCTAssert(tokenizer.postProcessor is ByteLevelProcessing)
+
+        let output = try tokenizer.encode("How are you 😁?")
+        XCTAssertFalse(output.tokens.contains("[UNK]"))
+        XCTAssertEqual(
+            try tokenizer.decode(output.ids, skipSpecialTokens: false), "How are you 😁?")
+    }
+
+    func testMetaspace() throws {
+        let vocab: Vocab = ["<unk>": 0, "▁Hello": 1, "▁world": 2, "!": 3]
+        let tokenizer = Tokenizer(model: try WordLevel(vocab: vocab, unkToken: "<unk>"))
+        tokenizer.preTokenizer = try Metaspace()
+        tokenizer.decoder = try MetaspaceDecoder()
+
+        let output = try tokenizer.encode("Hello world")
+        XCTAssertEqual(output.tokens, ["▁Hello", "▁world"])
+        XCTAssertEqual(try tokenizer.decode(output.ids, skipSpecialTokens: false), "Hello world")
+        XCTAssert(tokenizer.preTokenizer is Metaspace)
+        XCTAssert(tokenizer.decoder is MetaspaceDecoder)
+        XCTAssertThrowsError(try Metaspace(replacement: "__"))
+    }
+
+    func testPreTokenizers() throws {
+        let split = try Split(.regex("\\s+"), behavior: .removed)
+        XCTAssertEqual(try split.preTokenize("Hey  friend").map { $0.0 }, ["Hey", "friend"])
+        XCTAssertEqual(split.behavior, .removed)
+
+        let digits = Digits(individualDigits: true)
+        let output = try digits.preTokenize("Call 123")
+        XCTAssertEqual(output.map { $0.0 }, ["Call ", "1", "2", "3"])
+        XCTAssertEqual(output[1].1.start, 5)
+        XCTAssertEqual(output[1].1.end, 6)
+
+        XCTAssertEqual(
+            try Punctuation().preTokenize("Hi, there!").map { $0.0 }, ["Hi", ",", " there", "!"])
+        XCTAssertEqual(try CharDelimiterSplit("-").preTokenize("a-b").map { $0.0 }, ["a", "b"])
+        XCTAssertThrowsError(try CharDelimiterSplit("--"))
+
+        let tokenizer = Tokenizer(model: try WordLevel())
+        tokenizer.preTokenizer = BertPreTokenizer()
+        XCTAssert(tokenizer.preTokenizer is BertPreTokenizer)
+    }
+
+    func testPreTokenizerSequence() throws {
+        let tokenizer = Tokenizer(model: try WordLevel(vocab: ["[UNK]": 0], unkToken: "[UNK]"))
+        tokenizer.preTokenizer = PreTokenizerSequence([
+            WhitespaceSplit(), PreTokenizerSequence([Punctuation(), Digits(individualDigits: true)]),
+        ])
+
+        let path = FileManager.default.temporaryDirectory.appendingPathComponent(
+            "pre-tokenizer-sequence.json"
+        ).path
+        try tokenizer.save(to: path)
+
+        let saved = try Tokenizer(contentsOfFile: path)
+        let sequence = try XCTUnwrap(saved.preTokenizer as? PreTokenizerSequence)
+        XCTAssert(sequence.preTokenizers[0] is WhitespaceSplit)
+        XCTAssert(sequence.preTokenizers[1] is PreTokenizerSequence)
+        XCTAssertEqual(
+            try sequence.preTokenize("Hey 42!").map { $0.0 }, ["Hey", "4", "2", "!"])
+    }
+
+    func testDecoders() throws {
+        XCTAssertEqual(
+            try WordPieceDecoder().decode(["Hel", "##lo", "there", "!"]), "Hello there!")
+        XCTAssertEqual(try BPEDecoder().decode(["Hel", "lo</w>", "there</w>"]), "Hello there")
+        XCTAssertEqual(
+            try CTC().decode(["<pad>", "h", "h", "e", "<pad>", "y", "|", "y", "o"]), "hey yo")
+
+        let llama = DecoderSequence([
+            try ReplaceDecoder("▁", content: " "), ByteFallback(), Fuse(),
+            try StripDecoder(content: " ", start: 1),
+        ])
+        XCTAssertEqual(try llama.decode(["▁Hey", "<0x21>", "▁you"]), "Hey! you")
+        XCTAssert(llama.decoders[3] is StripDecoder)
+
+        let tokenizer = Tokenizer(model: try WordPiece(vocab: ["[UNK]": 0, "hey": 1, "##ho": 2]))
+        tokenizer.decoder = WordPieceDecoder()
+        XCTAssertEqual(try tokenizer.decode([1, 2], skipSpecialTokens: false), "heyho")
+        XCTAssert(tokenizer.decoder is WordPieceDecoder)
+    }
+
+    func testTemplateProcessing() throws {
+        let vocab: Vocab = ["[UNK]": 0, "[CLS]": 1, "[SEP]": 2, "hello": 3, "world": 4]
+        let tokenizer = Tokenizer(model: try WordLevel(vocab: vocab, unkToken: "[UNK]"))
+        tokenizer.preTokenizer = Whitespace()
+        tokenizer.postProcessor = try TemplateProcessing(
+            single: "[CLS] $A [SEP]", pair: "[CLS] $A [SEP] $B:1 [SEP]:1",
+            specialTokens: [("[CLS]", 1), ("[SEP]", 2)])
+
+        XCTAssert(tokenizer.postProcessor is TemplateProcessing)
+        XCTAssertEqual(tokenizer.numSpecialTokensToAdd(isPair: false), 2)
+        XCTAssertEqual(tokenizer.numSpecialTokensToAdd(isPair: true), 3)
+
+        let output = try tokenizer.encode("hello", pair: "world", addSpecialTokens: true)
+        XCTAssertEqual(output.tokens, ["[CLS]", "hello", "[SEP]", "world", "[SEP]"])
+        XCTAssertEqual(output.typeIds, [0, 0, 0, 1, 1])
+
+        tokenizer.postProcessor = BertProcessing(sep: ("[SEP]", 2), cls: ("[CLS]", 1))
+        XCTAssertEqual(
+            try tokenizer.encode("hello", addSpecialTokens: true).tokens,
+            ["[CLS]", "hello", "[SEP]"])
+        XCTAssertThrowsError(try TemplateProcessing(single: "[X] $A"))
+    }
+
+    func testEncodeSegments() throws {
+        let vocab: Vocab = ["[UNK]": 0, "[CLS]": 1, "[SEP]": 2, "a": 3, "b": 4, "c": 5]
+        let tokenizer = Tokenizer(model: try WordLevel(vocab: vocab, unkToken: "[UNK]"))
+        tokenizer.preTokenizer = Whitespace()
+        tokenizer.postProcessor = try TemplateProcessing(
+            single: "[CLS] $A [SEP]", pair: "[CLS] $A [SEP] $B:1 [SEP]:1",
+            specialTokens: [("[CLS]", 1), ("[SEP]", 2)])
+
+        let output = try tokenizer.encode(
+            segments: ["a", "b b", "c"], template: "[CLS] $A [SEP] $B:1 [SEP]:1 $C:2")
+        XCTAssertEqual(output.tokens, ["[CLS]", "a", "[SEP]", "b", "b", "[SEP]", "c"])
+        XCTAssertEqual(output.typeIds, [0, 0, 0, 1, 1, 1, 2])
+        XCTAssertEqual(output.sequenceIds, [nil, 0, nil, 1, 1, nil, 2])
+
+        XCTAssertEqual(try tokenizer.encode(segments: ["a", "b", "c"]).typeIds, [0, 1, 2])
+        XCTAssertThrowsError(try tokenizer.encode(segments: ["a", "b"], template: "$A $C"))
+        XCTAssertThrowsError(try tokenizer.encode(segments: ["a", "b"], template: "$A [UNK] $B"))
+
+        try tokenizer.setTruncation(TruncationParams(maxLength: 5, strategy: .onlyFirst))
+        tokenizer.padding = PaddingParams(strategy: .fixed(6), padId: 0, padToken: "[UNK]")
+        let truncated = try tokenizer.encode(
+            segments: ["a a a a a", "b"], template: "[CLS] $A [SEP] $B:1 [SEP]:1")
+        XCTAssertEqual(truncated.tokens, ["[CLS]", "a", "[SEP]", "b", "[SEP]", "[UNK]"])
+        XCTAssertEqual(truncated.overflowing.count, 4)
+
+        // Two segments are truncated like a pair, the odd token going to the longest one
+        try tokenizer.setTruncation(TruncationParams(maxLength: 12, strategy: .longestFirst))
+        tokenizer.padding = nil
+        let pair = try tokenizer.encode("a b c a b c a", pair: "c b a c b a")
+        let segments = try tokenizer.encode(
+            segments: ["a b c a b c a", "c b a c b a"], template: "[CLS] $A [SEP] $B:1 [SEP]:1")
+        XCTAssertEqual(segments.tokens, pair.tokens)
+        XCTAssertEqual(segments.sequenceIds, pair.sequenceIds)
+        XCTAssertEqual(segments.overflowing.count, pair.overflowing.count)
+    }
+
     func testAddSpecialTokens() throws {
         let model = try BPE(unkToken: "[UNK]")
         let tokenizer = Tokenizer(model: model)
@@ -85,6 +379,151 @@ final class TokenizerTests: XCTestCase {
             ])
     }
 
+    func testEncodingAlignment() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+        let output = try tokenizer.encode("Hello, y'all!", pair: "How are you")
+
+        XCTAssertEqual(output.nSequences, 2)
+        XCTAssertEqual(output.wordIds, [0, 1, 2, 3, 4, 5, 0, 1, 2])
+        XCTAssertEqual(output.sequenceIds, [0, 0, 0, 0, 0, 0, 1, 1, 1])
+        XCTAssertEqual(output.offsets[4].start, 9)
+        XCTAssertEqual(output.offsets[4].end, 12)
+
+        XCTAssertEqual(output.tokenToSequence(7), 1)
+        XCTAssertEqual(output.tokenToChars(7)?.start, 4)
+        XCTAssertNil(output.tokenToChars(7, sequenceIndex: 0))
+        XCTAssertEqual(output.tokenToWord(7, sequenceIndex: 1), 1)
+        XCTAssertEqual(output.charToToken(4, sequenceIndex: 1), 7)
+        XCTAssertEqual(output.charToWord(8), 3)
+        XCTAssertEqual(output.wordToTokens(2, sequenceIndex: 1), 8..<9)
+        XCTAssertEqual(output.wordToChars(1)?.end, 6)
+    }
+
+    func testEncodeOffsetType() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+        let text = "Hello 😁 ?"
+
+        let chars = try tokenizer.encode(text)
+        XCTAssertEqual(chars.offsets.last?.start, 8)
+
+        let bytes = try tokenizer.encode(text, offsetType: .byte)
+        XCTAssertEqual(bytes.offsets.last?.start, 11)
+
+        let utf16 = try tokenizer.encode(text, offsetType: .utf16)
+        XCTAssertEqual(utf16.offsets[1].start, 6)
+        XCTAssertEqual(utf16.offsets[1].end, 8)
+        XCTAssertEqual(utf16.offsets.last?.start, 9)
+    }
+
+    func testEncodeBatchToBuffer() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+
+        let buffer = try tokenizer.encodeBatchToBuffer(
+            ["Hello", "Hello, y'all!"], dtype: .int32, withPositionIds: true)
+        XCTAssertEqual(
+            buffer.names, ["input_ids", "attention_mask", "token_type_ids", "position_ids"])
+        XCTAssertEqual(buffer.shape, [4, 2, 6])
+        XCTAssertEqual(buffer.data.count, 4 * 2 * 6 * 4)
+
+        let mask = buffer.data(named: "attention_mask")!.withUnsafeBytes {
+            Array($0.bindMemory(to: Int32.self))
+        }
+        XCTAssertEqual(mask, [1, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1])
+
+        // The data keeps the Rust buffer alive once the `BatchBuffer` is gone
+        let data = try tokenizer.encodeBatchToBuffer(["Hello"], dtype: .int32).data(
+            named: "input_ids")!
+        let ids = data.withUnsafeBytes { Array($0.bindMemory(to: Int32.self)) }
+        XCTAssertEqual(ids, try tokenizer.encode("Hello").ids.map { Int32($0) })
+
+        tokenizer.padding = PaddingParams(padId: UInt32.max)
+        XCTAssertThrowsError(
+            try tokenizer.encodeBatchToBuffer(["Hello", "Hello, y'all!"], dtype: .int32))
+        XCTAssertEqual(
+            try tokenizer.encodeBatchToBuffer(["Hello", "Hello, y'all!"], dtype: .int64).data.count,
+            3 * 2 * 6 * 8)
+    }
+
+    func testEncodeBatch() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+
+        let outputs = try tokenizer.encodeBatch(["Hello, y'all!", .preTokenized(["How", "are"])])
+        XCTAssertEqual(outputs.count, 2)
+        XCTAssertEqual(outputs[0].tokens, ["Hello", ",", "y", "\'", "all", "!"])
+        XCTAssertEqual(outputs[1].tokens, ["How", "are"])
+
+        let pairs = try tokenizer.encodeBatch(pairs: [("Hello", "you")])
+        XCTAssertEqual(pairs[0].tokens, ["Hello", "you"])
+        XCTAssertEqual(pairs[0].typeIds, [0, 1])
+    }
+
+    func testPadding() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+        XCTAssertNil(tokenizer.padding)
+
+        tokenizer.padding = PaddingParams(strategy: .fixed(4), padId: 3)
+        let output = try tokenizer.encode("Hello")
+        XCTAssertEqual(output.tokens, ["Hello", "[PAD]", "[PAD]", "[PAD]"])
+        XCTAssertEqual(output.ids[1], 3)
+        XCTAssertEqual(output.attentionMask, [1, 0, 0, 0])
+
+        tokenizer.padding = PaddingParams(direction: .left)
+        let outputs = try tokenizer.encodeBatch(["Hello", "Hello, y'all!"])
+        XCTAssertEqual(outputs[0].tokens, ["[PAD]", "[PAD]", "[PAD]", "[PAD]", "[PAD]", "Hello"])
+
+        tokenizer.padding = nil
+        XCTAssertNil(tokenizer.padding)
+    }
+
+    func testTruncation() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+        XCTAssertNil(tokenizer.truncation)
+
+        try tokenizer.setTruncation(TruncationParams(maxLength: 4, stride: 1))
+        XCTAssertEqual(tokenizer.truncation?.maxLength, 4)
+
+        let output = try tokenizer.encode("Hello, y'all! How are you")
+        XCTAssertEqual(output.tokens, ["Hello", ",", "y", "\'"])
+        XCTAssertEqual(
+            output.overflowing.map { $0.tokens },
+            [["\'", "all", "!", "How"], ["How", "are", "you"]])
+
+        XCTAssertThrowsError(try tokenizer.setTruncation(TruncationParams(maxLength: 2, stride: 2)))
+
+        // The pair is truncated to 3 + 2 tokens, which leaves no room for the stride
+        try tokenizer.setTruncation(
+            TruncationParams(maxLength: 5, stride: 2, strategy: .longestFirst))
+        XCTAssertThrowsError(
+            try tokenizer.encode("Hello, y'all! How are you", pair: "I am fine, thank you"))
+        XCTAssertThrowsError(
+            try tokenizer.encodeBatch(
+                pairs: [("Hello, y'all! How are you", "I am fine, thank you")]))
+
+        // A post-processor set afterwards leaves 4 - 2 tokens, which is no room for the stride
+        try tokenizer.setTruncation(TruncationParams(maxLength: 4, stride: 2))
+        tokenizer.postProcessor = BertProcessing(sep: ("[SEP]", 2), cls: ("[CLS]", 1))
+        XCTAssertThrowsError(try tokenizer.encode("Hello, y'all! How are you"))
+        XCTAssertThrowsError(try tokenizer.encodeBatch(["Hello, y'all! How are you"]))
+
+        try tokenizer.setTruncation(TruncationParams(maxLength: 1))
+        XCTAssertThrowsError(try tokenizer.encode("Hello, y'all! How are you"))
+
+        try tokenizer.setTruncation(nil)
+        XCTAssertNil(tokenizer.truncation)
+    }
+
     func testDecode() throws {
         let filePath = Bundle.module.path(
             forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
@@ -95,4 +534,171 @@ final class TokenizerTests: XCTestCase {
 
         XCTAssertEqual(decoded, "Hello , y ' all ! How are you [UNK] ?")
     }
+
+    func testDecodeStream() throws {
+        let vocab: Vocab = ["[UNK]": 0, "Hi": 1, "<0xF0>": 2, "<0x9F>": 3, "<0x98>": 4, "<0x81>": 5]
+        let tokenizer = Tokenizer(model: try WordPiece(vocab: vocab, unkToken: "[UNK]"))
+        tokenizer.decoder = ByteFallback()
+
+        let stream = tokenizer.decodeStream()
+        let texts = try [1, 2, 3, 4, 5, 1, 2].map { try stream.step($0) }
+        XCTAssertEqual(texts, ["Hi", nil, nil, nil, "😁", "Hi", nil])
+        XCTAssertEqual(try stream.flush(), "�")
+        XCTAssertNil(try stream.flush())
+    }
+
+    func testStopSequenceMatcher() throws {
+        let vocab: Vocab = [
+            "[UNK]": 0, "Hi": 1, "<0xF0>": 2, "<0x9F>": 3, "<0x98>": 4, "<0x81>": 5, "!": 6,
+        ]
+        let tokenizer = Tokenizer(model: try WordPiece(vocab: vocab, unkToken: "[UNK]"))
+        tokenizer.decoder = ByteFallback()
+
+        let matcher = try tokenizer.stopSequenceMatcher(["😁!"])
+        for id: UInt32 in [1, 2, 3, 4, 5] {
+            XCTAssertNil(try matcher.step(id))
+        }
+
+        let match = try matcher.step(6)
+        XCTAssertEqual(match?.stop, "😁!")
+        XCTAssertEqual(match?.charIndex, 2)
+        XCTAssertEqual(match?.tokenIndex, 1)
+
+        XCTAssertThrowsError(try tokenizer.stopSequenceMatcher([""]))
+    }
+
+    func testDecodeBatch() throws {
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+        let ids = try tokenizer.encode("Hello, y'all!").ids
+
+        let decoded = try tokenizer.decodeBatch([ids, Array(ids[..<2])], skipSpecialTokens: true)
+        XCTAssertEqual(decoded, ["Hello , y ' all !", "Hello ,"])
+    }
+
+    func testDecodePieces() throws {
+        let vocab: Vocab = ["[UNK]": 0, "Hi": 1, "<0xF0>": 2, "<0x9F>": 3, "<0x98>": 4, "<0x81>": 5]
+        let tokenizer = Tokenizer(model: try WordPiece(vocab: vocab, unkToken: "[UNK]"))
+        tokenizer.decoder = ByteFallback()
+
+        let pieces = try tokenizer.decodePieces([1, 2, 3, 4, 5, 1], skipSpecialTokens: false)
+        XCTAssertEqual(pieces.map { $0.text }, ["Hi", "", "", "", "😁", "Hi"])
+        XCTAssertEqual(pieces[4].offsets.start, 2)
+        XCTAssertEqual(pieces[4].offsets.end, 3)
+        XCTAssertEqual(pieces[5].offsets.start, 3)
+    }
+
+    func testChatTemplate() throws {
+        let template = try ChatTemplate(
+            """
+            {% for message in messages %}
+                {% if message.role == 'system' %}
+                    {{ raise_exception('System messages are not supported') }}
+                {% endif %}
+                {{- bos_token + '[' + message.role|upper + '] ' + message.content|trim -}}
+            {% endfor %}
+            {% if add_generation_prompt %}[ASSISTANT]{% endif %}
+            """, bosToken: "<s>")
+        let messages = [
+            ChatMessage(role: "user", content: " Hello "),
+            ChatMessage(role: "assistant", content: "Hi!"),
+        ]
+
+        XCTAssertEqual(try template.render(messages), "<s>[USER] Hello<s>[ASSISTANT] Hi!")
+        XCTAssertEqual(
+            try template.render(messages, addGenerationPrompt: true),
+            "<s>[USER] Hello<s>[ASSISTANT] Hi![ASSISTANT]")
+        XCTAssertThrowsError(try template.render([ChatMessage(role: "system", content: "")]))
+
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+        let encoding = try template.encode(Array(messages[..<1]), with: tokenizer)
+        XCTAssertEqual(encoding.tokens, ["<", "s", ">", "[", "US", "ER", "]", "Hello"])
+    }
+
+    func testChatTemplateAssistantMasks() throws {
+        let template = try ChatTemplate(
+            """
+            {% for message in messages %}
+                {{- message.role + ': ' + message.content + ' .\n' -}}
+            {% endfor %}
+            {% if add_generation_prompt %}assistant: {% endif %}
+            """)
+        let filePath = Bundle.module.path(
+            forResource: "tokenizer-wiki", ofType: "json", inDirectory: "Files")!
+        let tokenizer = try Tokenizer(contentsOfFile: filePath)
+
+        let chat = try template.encodeWithAssistantMasks(
+            [
+                ChatMessage(role: "user", content: "Hi"),
+                ChatMessage(role: "assistant", content: "Hello there"),
+            ], with: tokenizer)
+        XCTAssertEqual(
+            chat.encoding.tokens, ["user", ":", "Hi", ".", "assistant", ":", "Hello", "there", "."])
+        XCTAssertEqual(chat.assistantMasks, [0, 0, 0, 0, 0, 0, 1, 1, 1])
+        XCTAssertEqual(chat.labels[..<6], [-100, -100, -100, -100, -100, -100])
+        XCTAssertEqual(chat.labels[6...].map { UInt32($0) }, Array(chat.encoding.ids[6...]))
+
+        let generationTemplate = try ChatTemplate(
+            """
+            {% for message in messages %}
+                {{- message.role + ': ' -}}
+                {% if message.role == 'assistant' %}
+                    {% generation %}{{ message.content }}{% endgeneration %}
+                {% else %}
+                    {{- message.content -}}
+                {% endif %}
+                {{- ' .\n' -}}
+            {% endfor %}
+            """)
+        let generationChat = try generationTemplate.encodeWithAssistantMasks(
+            [
+                ChatMessage(role: "user", content: "Hi"),
+                ChatMessage(role: "assistant", content: "Hello there"),
+            ], with: tokenizer)
+        XCTAssertEqual(generationChat.encoding.tokens, chat.encoding.tokens)
+        XCTAssertEqual(generationChat.assistantMasks, [0, 0, 0, 0, 0, 0, 1, 1, 0])
+
+        // The characters marking the blocks are removed from the messages
+        let markedChat = try generationTemplate.encodeWithAssistantMasks(
+            [
+                ChatMessage(role: "user", content: "Hi\u{FDD1}"),
+                ChatMessage(role: "assistant", content: "Hello\u{FDD0} there"),
+            ], with: tokenizer)
+        XCTAssertEqual(markedChat.encoding.tokens, chat.encoding.tokens)
+        XCTAssertEqual(markedChat.assistantMasks, generationChat.assistantMasks)
+    }
+
+    func testChatTemplateFeatures() throws {
+        let messages = [
+            ChatMessage(role: "user", content: "Hi <b>"),
+            ChatMessage(role: "assistant", content: "Hello"),
+        ]
+        func render(_ template: String) throws -> String {
+            try ChatTemplate(template).render(messages)
+        }
+
+        XCTAssertEqual(
+            try render("{% macro square(x) %}{{ x ** 2 }}{% endmacro %}{{ square(2 ** 3) }}"), "64")
+        XCTAssertEqual(
+            try render(
+                "{% for message in messages %}{{ loop.previtem.role if loop.previtem else '^' }}"
+                    + ">{{ loop.nextitem.role if loop.nextitem else '$' }};{% endfor %}"),
+            "^>assistant;user>$;")
+        XCTAssertEqual(
+            try render("{{ strftime_now('%Y') }}"),
+            String(Calendar.current.component(.year, from: Date())))
+        XCTAssertEqual(
+            try render("{{ messages[0]|tojson }}"), #"{"role": "user", "content": "Hi <b>"}"#)
+        XCTAssertEqual(
+            try render("{{ messages[1].content.lower().startswith('he') }}"), "True")
+        XCTAssertEqual(try render("{{ messages[0].get('name', 'anonymous') }}"), "anonymous")
+
+        // Overflows and oversized values are errors
+        XCTAssertThrowsError(try render("{{ 2 ** 200 }}"))
+        XCTAssertThrowsError(try render("{{ range(1000000)|length }}"))
+        XCTAssertThrowsError(try render("{{ strftime_now('%Q') }}"))
+    }
 }
@@ -0,0 +1,244 @@
+mod jinja;
+
+use crate::error::{Result, TokenizersError};
+use crate::{RustEncoding, RustInputSequence, RustOffsetType, RustTokenizer};
+use jinja::{strip_generation_markers, Template};
+use minijinja::{context, Value};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The label of the tokens the loss ignores.
+const IGNORE_INDEX: i64 = -100;
+
+/// A message of a conversation.
+pub struct RustChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl From<&RustChatMessage> for Value {
+    fn from(message: &RustChatMessage) -> Self {
+        context! {
+            role => strip_generation_markers(&message.role),
+            content => strip_generation_markers(&message.content),
+        }
+    }
+}
+
+/// A conversation encoded for supervised fine-tuning, where the loss only covers what the
+/// assistant says.
+pub struct RustChatEncoding {
+    pub encoding: Arc<RustEncoding>,
+    /// 1 for the tokens of the assistant messages, and 0 elsewhere.
+    pub assistant_masks: Vec<u32>,
+    /// The ids of the assistant tokens, and -100 elsewhere.
+    pub labels: Vec<i64>,
+}
+
+/// The Jinja template that formats a conversation into the prompt of a chat model.
+pub struct RustChatTemplate {
+    template: Template,
+    bos_token: Option<String>,
+    eos_token: Option<String>,
+}
+
+impl RustChatTemplate {
+    pub fn new(
+        template: &str,
+        bos_token: Option<String>,
+        eos_token: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self {
+            template: Template::parse(template)?,
+            bos_token,
+            eos_token,
+        })
+    }
+
+    /// Loads the `chat_template` of a `tokenizer_config.json` file. When the file has
+    /// several named templates, the `default` one is used.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| TokenizersError::Exception(format!("{}: {}", path, err)))?;
+        let config: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|err| TokenizersError::ValueError(format!("{}: {}", path, err)))?;
+
+        let template = match &config["chat_template"] {
+            serde_json::Value::String(template) => template.as_str(),
+            serde_json::Value::Array(templates) => templates
+                .iter()
+                .find(|t| t["name"] == "default")
+                .and_then(|t| t["template"].as_str())
+                .ok_or_else(|| {
+                    TokenizersError::ValueError(format!("{}: no default chat template", path))
+                })?,
+            _ => {
+                return Err(TokenizersError::ValueError(format!(
+                    "{}: no chat template",
+                    path
+                )))
+            }
+        };
+
+        // Special tokens are either strings or serialized `AddedToken`s
+        let special_token = |name: &str| match &config[name] {
+            serde_json::Value::String(token) => Some(token.clone()),
+            token => token["content"].as_str().map(Into::into),
+        };
+
+        Self::new(
+            template,
+            special_token("bos_token"),
+            special_token("eos_token"),
+        )
+    }
+
+    pub fn get_bos_token(&self) -> Option<String> {
+        self.bos_token.clone()
+    }
+
+    pub fn get_eos_token(&self) -> Option<String> {
+        self.eos_token.clone()
+    }
+
+    pub fn render(
+        &self,
+        messages: Vec<RustChatMessage>,
+        add_generation_prompt: bool,
+    ) -> Result<String> {
+        let (prompt, _) = self.render_messages(&messages, add_generation_prompt)?;
+        Ok(prompt)
+    }
+
+    /// Renders the conversation, along with the char spans of its `{% generation %}` blocks.
+    fn render_messages(
+        &self,
+        messages: &[RustChatMessage],
+        add_generation_prompt: bool,
+    ) -> Result<(String, Vec<Range<usize>>)> {
+        let messages: Vec<Value> = messages.iter().map(Into::into).collect();
+
+        self.template.render(context! {
+            messages,
+            add_generation_prompt,
+            bos_token => self.bos_token.as_deref().map(strip_generation_markers),
+            eos_token => self.eos_token.as_deref().map(strip_generation_markers),
+        })
+    }
+
+    /// Renders the conversation, and returns the char span of each assistant message in the
+    /// prompt.
+    ///
+    /// When the template has `{% generation %}` blocks, like in `transformers`, the spans are
+    /// their content. Otherwise, a span is what the model generates after the prompt of the
+    /// previous messages, so it includes the end-of-turn tokens of the template.
+    fn render_assistant_spans(
+        &self,
+        messages: &[RustChatMessage],
+    ) -> Result<(String, Vec<Range<usize>>)> {
+        if self.template.has_generation_blocks() {
+            return self.render_messages(messages, false);
+        }
+
+        let misplaced = |i: usize| {
+            TokenizersError::ValueError(format!(
+                "The chat template doesn't render the message {} after the previous ones",
+                i
+            ))
+        };
+
+        // Each prefix of the conversation is rendered once, and must extend the previous one,
+        // so the spans are counted from where it ends
+        let mut prefix = String::new();
+        let mut prefix_length = 0;
+        let mut spans = vec![];
+        for (i, message) in messages.iter().enumerate() {
+            if message.role != "assistant" {
+                continue;
+            }
+
+            let (before, _) = self.render_messages(&messages[..i], true)?;
+            let (after, _) = self.render_messages(&messages[..=i], false)?;
+            if !before.starts_with(&prefix) || !after.starts_with(&before) {
+                return Err(misplaced(i));
+            }
+
+            let start = prefix_length + before[prefix.len()..].chars().count();
+            let end = start + after[before.len()..].chars().count();
+            spans.push(start..end);
+
+            prefix = after;
+            prefix_length = end;
+        }
+
+        let prompt = match messages.last() {
+            Some(message) if message.role == "assistant" => prefix,
+            _ => {
+                let (prompt, _) = self.render_messages(messages, false)?;
+                if !prompt.starts_with(&prefix) {
+                    return Err(misplaced(messages.len() - 1));
+                }
+                prompt
+            }
+        };
+
+        Ok((prompt, spans))
+    }
+
+    /// Renders the conversation and encodes it. The template already adds the special
+    /// tokens, so the post-processor of the tokenizer is not applied.
+    pub fn encode(
+        &self,
+        tokenizer: Arc<RustTokenizer>,
+        messages: Vec<RustChatMessage>,
+        add_generation_prompt: bool,
+    ) -> Result<Arc<RustEncoding>> {
+        let prompt = self.render(messages, add_generation_prompt)?;
+
+        tokenizer.encode(
+            RustInputSequence::Raw { raw_value: prompt },
+            None,
+            false,
+            None,
+        )
+    }
+
+    /// Encodes the conversation like `encode`, and marks the tokens of the assistant
+    /// messages, which are the ones to train on.
+    pub fn encode_with_assistant_masks(
+        &self,
+        tokenizer: Arc<RustTokenizer>,
+        messages: Vec<RustChatMessage>,
+    ) -> Result<RustChatEncoding> {
+        let (prompt, spans) = self.render_assistant_spans(&messages)?;
+        let encoding = tokenizer.encode(
+            RustInputSequence::Raw { raw_value: prompt },
+            None,
+            false,
+            Some(RustOffsetType::Char),
+        )?;
+
+        // A token is trained on as soon as it overlaps an assistant message
+        let assistant_masks: Vec<u32> = encoding
+            .get_offsets()
+            .iter()
+            .map(|&(start, end)| {
+                spans
+                    .iter()
+                    .any(|span| start < span.end && end > span.start) as u32
+            })
+            .collect();
+        let labels = encoding
+            .get_ids()
+            .iter()
+            .zip(&assistant_masks)
+            .map(|(&id, &mask)| if mask == 1 { id as i64 } else { IGNORE_INDEX })
+            .collect();
+
+        Ok(RustChatEncoding {
+            encoding,
+            assistant_masks,
+            labels,
+        })
+    }
+}
@@ -0,0 +1,311 @@
+//! The Jinja environment of the chat templates, which mirrors the one of `transformers`.
+//!
+//! The templates are rendered with `trim_blocks` and `lstrip_blocks`, and have access to
+//! `raise_exception()`, `strftime_now()`, a `tojson` filter formatting like Python's
+//! `json.dumps`, the methods of Python's `str`, `dict` and `list`, and `{% generation %}` blocks.
+
+use crate::error::{Result, TokenizersError};
+use chrono::format::{Item, StrftimeItems};
+use minijinja::value::{Kwargs, ValueKind};
+use minijinja::{escape_formatter, Environment, Error, ErrorKind, Output, State, Value};
+use minijinja_contrib::pycompat;
+use serde::Serialize;
+use serde_json::ser::{Formatter, PrettyFormatter};
+use std::fmt::Write;
+use std::io;
+use std::ops::Range;
+
+/// The name of the only template of the environment.
+const NAME: &str = "chat_template";
+
+/// `{% generation %}` is not a Jinja tag, so each block is rewritten into an `if` block
+/// outputting these noncharacters, which Unicode reserves for internal use, around its
+/// content. They are removed from the values of the context, so that the output only has
+/// the ones of the blocks.
+const GENERATION_START: char = '\u{FDD0}';
+const GENERATION_END: char = '\u{FDD1}';
+
+/// Removes the generation markers from a string of the context.
+pub(crate) fn strip_generation_markers(text: &str) -> String {
+    text.replace([GENERATION_START, GENERATION_END], "")
+}
+
+/// The error of `raise_exception()`, kept as the source of the rendering error.
+#[derive(thiserror::Error, Debug)]
+#[error("{0}")]
+struct RaisedException(String);
+
+fn error(err: Error) -> TokenizersError {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&err);
+    while let Some(err) = source {
+        if let Some(RaisedException(message)) = err.downcast_ref() {
+            return TokenizersError::Exception(message.clone());
+        }
+        source = err.source();
+    }
+
+    TokenizersError::ValueError(format!("Chat template: {}", err))
+}
+
+pub(crate) struct Template {
+    environment: Environment<'static>,
+    has_generation_blocks: bool,
+}
+
+impl Template {
+    pub(crate) fn parse(source: &str) -> Result<Self> {
+        let (rewritten, has_generation_blocks) = rewrite_generation_blocks(source);
+        if has_generation_blocks && source.contains([GENERATION_START, GENERATION_END]) {
+            return Err(TokenizersError::ValueError(
+                "Chat template: U+FDD0 and U+FDD1 are reserved for `{% generation %}` blocks"
+                    .into(),
+            ));
+        }
+
+        let mut environment = Environment::new();
+        environment.set_trim_blocks(true);
+        environment.set_lstrip_blocks(true);
+        environment.set_formatter(format_value);
+        environment.set_unknown_method_callback(call_method);
+        environment.add_function("raise_exception", raise_exception);
+        environment.add_function("strftime_now", strftime_now);
+        environment.add_filter("tojson", tojson);
+        environment
+            .add_template_owned(NAME, rewritten)
+            .map_err(error)?;
+
+        Ok(Self {
+            environment,
+            has_generation_blocks,
+        })
+    }
+
+    pub(crate) fn has_generation_blocks(&self) -> bool {
+        self.has_generation_blocks
+    }
+
+    /// Renders the template, and returns the char span of each `{% generation %}` block
+    /// in the output.
+    pub(crate) fn render(&self, context: Value) -> Result<(String, Vec<Range<usize>>)> {
+        let output = self
+            .environment
+            .get_template(NAME)
+            .and_then(|template| template.render(context))
+            .map_err(error)?;
+        if !self.has_generation_blocks {
+            return Ok((output, vec![]));
+        }
+
+        let mut text = String::with_capacity(output.len());
+        let mut spans = vec![];
+        let mut start = None;
+        let mut length = 0;
+        for c in output.chars() {
+            match (c, start) {
+                (GENERATION_START, None) => start = Some(length),
+                (GENERATION_END, Some(span_start)) => {
+                    spans.push(span_start..length);
+                    start = None;
+                }
+                (GENERATION_START | GENERATION_END, _) => {
+                    return Err(TokenizersError::ValueError(
+                        "Chat template: `{% generation %}` blocks can't be nested".into(),
+                    ))
+                }
+                _ => {
+                    text.push(c);
+                    length += 1;
+                }
+            }
+        }
+
+        Ok((text, spans))
+    }
+}
+
+/// Rewrites the `{% generation %}` and `{% endgeneration %}` tags of the source, keeping
+/// their whitespace control, and returns whether there were any.
+fn rewrite_generation_blocks(source: &str) -> (String, bool) {
+    let mut rewritten = String::with_capacity(source.len());
+    let mut found = false;
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{%") {
+        rewritten.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        match generation_tag(rest) {
+            Some((length, replacement)) => {
+                rewritten.push_str(&replacement);
+                rest = &rest[length..];
+                found = true;
+            }
+            None => {
+                rewritten.push_str("{%");
+                rest = &rest[2..];
+            }
+        }
+    }
+    rewritten.push_str(rest);
+
+    (rewritten, found)
+}
+
+/// Parses the `{% generation %}` or `{% endgeneration %}` tag at the start of `source`,
+/// and returns its length and its replacement.
+fn generation_tag(source: &str) -> Option<(usize, String)> {
+    let length = source[2..].find("%}")? + 4;
+    let body = &source[2..length - 2];
+
+    let is_control = |c: char| c == '-' || c == '+';
+    let (left, body) = match body.chars().next() {
+        Some(c) if is_control(c) => body.split_at(1),
+        _ => ("", body),
+    };
+    let (body, right) = match body.chars().last() {
+        Some(c) if is_control(c) => body.split_at(body.len() - 1),
+        _ => (body, ""),
+    };
+
+    let replacement = match body.trim() {
+        "generation" => format!(
+            "{{%{} if true %}}{{{{ '{}' }}}}{{% if true {}%}}",
+            left, GENERATION_START, right
+        ),
+        "endgeneration" => format!(
+            "{{%{} endif %}}{{{{ '{}' }}}}{{% endif {}%}}",
+            left, GENERATION_END, right
+        ),
+        _ => return None,
+    };
+
+    Some((length, replacement))
+}
+
+fn raise_exception(message: String) -> std::result::Result<Value, Error> {
+    Err(Error::new(
+        ErrorKind::InvalidOperation,
+        "the template raised an exception",
+    )
+    .with_source(RaisedException(message)))
+}
+
+fn strftime_now(format: &str) -> std::result::Result<String, Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("invalid `strftime_now` format {:?}", format),
+        )
+    };
+
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.contains(&Item::Error) {
+        return Err(invalid());
+    }
+
+    let mut now = String::new();
+    write!(
+        now,
+        "{}",
+        chrono::Local::now().format_with_items(items.iter())
+    )
+    .map_err(|_| invalid())?;
+    Ok(now)
+}
+
+/// Formats the JSON like `json.dumps` does without `indent`.
+struct PythonFormatter;
+
+impl Formatter for PythonFormatter {
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        writer.write_all(b": ")
+    }
+}
+
+/// Unlike the `tojson` of Jinja, the one of `transformers` doesn't escape HTML characters.
+fn tojson(
+    value: &Value,
+    indent: Option<usize>,
+    kwargs: Kwargs,
+) -> std::result::Result<Value, Error> {
+    let indent = match indent {
+        Some(indent) => Some(indent),
+        None => kwargs.get::<Option<usize>>("indent")?,
+    };
+    kwargs.assert_all_used()?;
+
+    let mut json = vec![];
+    let serialized = match indent {
+        Some(indent) => {
+            let indent = " ".repeat(indent);
+            let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+            value.serialize(&mut serde_json::Serializer::with_formatter(
+                &mut json, formatter,
+            ))
+        }
+        None => value.serialize(&mut serde_json::Serializer::with_formatter(
+            &mut json,
+            PythonFormatter,
+        )),
+    };
+    serialized.map_err(|err| {
+        Error::new(ErrorKind::BadSerialization, "cannot serialize to JSON").with_source(err)
+    })?;
+
+    Ok(Value::from_safe_string(
+        String::from_utf8(json).expect("JSON must be UTF-8"),
+    ))
+}
+
+/// Outputs the booleans the way Python prints them.
+fn format_value(out: &mut Output, state: &State, value: &Value) -> std::result::Result<(), Error> {
+    match value.kind() {
+        ValueKind::Bool => {
+            let text = if value.is_true() { "True" } else { "False" };
+            out.write_str(text).map_err(Error::from)
+        }
+        _ => escape_formatter(out, state, value),
+    }
+}
+
+/// The methods of Python's `str`, `dict` and `list` come from `pycompat`, which doesn't take
+/// the default of `dict.get`.
+fn call_method(
+    state: &State,
+    value: &Value,
+    name: &str,
+    arguments: &[Value],
+) -> std::result::Result<Value, Error> {
+    match (value.kind(), name, arguments) {
+        (ValueKind::Map, "get", [key, default]) => match value.get_item(key)? {
+            item if item.is_undefined() => Ok(default.clone()),
+            item => Ok(item),
+        },
+        _ => pycompat::unknown_method_callback(state, value, name, arguments),
+    }
+}
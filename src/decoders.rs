@@ -0,0 +1,635 @@
+use crate::error::Result;
+use crate::utils::{serialized_field, single_char, RustPattern, RustUSize};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tk::decoders::bpe::BPEDecoder;
+use tk::decoders::byte_fallback::ByteFallback;
+use tk::decoders::byte_level::ByteLevel;
+use tk::decoders::ctc::CTC;
+use tk::decoders::fuse::Fuse;
+use tk::decoders::metaspace::Metaspace;
+use tk::decoders::sequence::Sequence;
+use tk::decoders::strip::Strip;
+use tk::decoders::wordpiece::WordPiece;
+use tk::normalizers::replace::Replace;
+use tk::{Decoder, DecoderWrapper};
+use tokenizers as tk;
+
+/// Base class for all decoders
+///
+/// This class is not supposed to be instantiated directly. Instead, any implementation of
+/// a Decoder will return an instance of this class when converted with `as_decoder`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustDecoder {
+    #[serde(flatten)]
+    pub(crate) decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl From<DecoderWrapper> for RustDecoder {
+    fn from(decoder: DecoderWrapper) -> Self {
+        Self {
+            decoder: Arc::new(RwLock::new(decoder)),
+        }
+    }
+}
+
+impl Decoder for RustDecoder {
+    fn decode_chain(&self, tokens: Vec<String>) -> tk::Result<Vec<String>> {
+        // Some upstream decoders (e.g. BPEDecoder) panic on an empty list of tokens.
+        if tokens.is_empty() {
+            return Ok(tokens);
+        }
+
+        self.decoder.read().unwrap().decode_chain(tokens)
+    }
+}
+
+impl RustDecoder {
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        Ok(self.decode(tokens)?)
+    }
+
+    /// Returns the concrete ByteLevel decoder handle sharing this decoder, if any.
+    pub fn as_byte_level(&self) -> Option<Arc<RustByteLevelDecoder>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::ByteLevel(_) => Some(Arc::new(RustByteLevelDecoder {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Metaspace decoder handle sharing this decoder, if any.
+    pub fn as_metaspace(&self) -> Option<Arc<RustMetaspaceDecoder>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::Metaspace(_) => Some(Arc::new(RustMetaspaceDecoder {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete WordPiece decoder handle sharing this decoder, if any.
+    pub fn as_wordpiece(&self) -> Option<Arc<RustWordPieceDecoder>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::WordPiece(_) => Some(Arc::new(RustWordPieceDecoder {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete BPE decoder handle sharing this decoder, if any.
+    pub fn as_bpe(&self) -> Option<Arc<RustBpeDecoder>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::BPE(_) => Some(Arc::new(RustBpeDecoder {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete CTC decoder handle sharing this decoder, if any.
+    pub fn as_ctc(&self) -> Option<Arc<RustCtc>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::CTC(_) => Some(Arc::new(RustCtc {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Replace decoder handle sharing this decoder, if any.
+    pub fn as_replace(&self) -> Option<Arc<RustReplaceDecoder>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::Replace(_) => Some(Arc::new(RustReplaceDecoder {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Fuse decoder handle sharing this decoder, if any.
+    pub fn as_fuse(&self) -> Option<Arc<RustFuse>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::Fuse(_) => Some(Arc::new(RustFuse {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Strip decoder handle sharing this decoder, if any.
+    pub fn as_strip(&self) -> Option<Arc<RustStripDecoder>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::Strip(_) => Some(Arc::new(RustStripDecoder {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete ByteFallback decoder handle sharing this decoder, if any.
+    pub fn as_byte_fallback(&self) -> Option<Arc<RustByteFallback>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::ByteFallback(_) => Some(Arc::new(RustByteFallback {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Sequence decoder handle sharing this decoder, if any.
+    pub fn as_sequence(&self) -> Option<Arc<RustDecoderSequence>> {
+        match *self.decoder.read().unwrap() {
+            DecoderWrapper::Sequence(_) => Some(Arc::new(RustDecoderSequence {
+                decoder: self.decoder.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// ByteLevel Decoder
+///
+/// This decoder is to be used in tandem with the ByteLevel pre-tokenizer.
+/// It maps the byte-level alphabet back to the original bytes.
+pub struct RustByteLevelDecoder {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl Default for RustByteLevelDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustByteLevelDecoder {
+    pub fn new() -> Self {
+        Self {
+            decoder: Arc::new(RwLock::new(ByteLevel::default().into())),
+        }
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this ByteLevel decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// Metaspace Decoder
+///
+/// This decoder is to be used in tandem with the Metaspace pre-tokenizer.
+/// It turns the replacement character back into spaces.
+pub struct RustMetaspaceDecoder {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl RustMetaspaceDecoder {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Metaspace) -> R,
+    {
+        if let DecoderWrapper::Metaspace(metaspace) = &*self.decoder.read().unwrap() {
+            callback(metaspace)
+        } else {
+            unreachable!("the decoder must be a `Metaspace`")
+        }
+    }
+}
+
+impl RustMetaspaceDecoder {
+    pub fn new(replacement: Option<String>, add_prefix_space: Option<bool>) -> Result<Self> {
+        let mut metaspace = Metaspace::default();
+
+        if let Some(replacement) = replacement {
+            metaspace.set_replacement(single_char(&replacement, "replacement")?);
+        }
+        if let Some(add_prefix_space) = add_prefix_space {
+            metaspace.add_prefix_space = add_prefix_space;
+        }
+
+        Ok(Self {
+            decoder: Arc::new(RwLock::new(metaspace.into())),
+        })
+    }
+
+    pub fn get_replacement(&self) -> String {
+        self.with_subtype(|metaspace| metaspace.get_replacement().to_string())
+    }
+
+    pub fn get_add_prefix_space(&self) -> bool {
+        self.with_subtype(|metaspace| metaspace.add_prefix_space)
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this Metaspace decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// WordPiece Decoder
+///
+/// This decoder is to be used in tandem with the WordPiece model. It merges the subwords
+/// starting with the continuing prefix back into words.
+pub struct RustWordPieceDecoder {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl RustWordPieceDecoder {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&WordPiece) -> R,
+    {
+        if let DecoderWrapper::WordPiece(inner) = &*self.decoder.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the decoder must be a `WordPiece`")
+        }
+    }
+}
+
+impl RustWordPieceDecoder {
+    pub fn new(prefix: Option<String>, cleanup: Option<bool>) -> Self {
+        let mut wordpiece = WordPiece::default();
+
+        if let Some(prefix) = prefix {
+            wordpiece.prefix = prefix;
+        }
+        if let Some(cleanup) = cleanup {
+            wordpiece.cleanup = cleanup;
+        }
+
+        Self {
+            decoder: Arc::new(RwLock::new(wordpiece.into())),
+        }
+    }
+
+    pub fn get_prefix(&self) -> String {
+        self.with_subtype(|wordpiece| wordpiece.prefix.clone())
+    }
+
+    pub fn get_cleanup(&self) -> bool {
+        self.with_subtype(|wordpiece| wordpiece.cleanup)
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this WordPiece decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// BPE Decoder
+///
+/// This decoder is to be used in tandem with the BPE model. It uses the end-of-word suffix
+/// to know where to put the spaces back.
+pub struct RustBpeDecoder {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl RustBpeDecoder {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&BPEDecoder) -> R,
+    {
+        if let DecoderWrapper::BPE(inner) = &*self.decoder.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the decoder must be a `BPE`")
+        }
+    }
+}
+
+impl RustBpeDecoder {
+    pub fn new(suffix: Option<String>) -> Self {
+        let bpe = suffix.map(BPEDecoder::new).unwrap_or_default();
+
+        Self {
+            decoder: Arc::new(RwLock::new(bpe.into())),
+        }
+    }
+
+    pub fn get_suffix(&self) -> String {
+        self.with_subtype(|bpe| bpe.suffix.clone())
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this BPE decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// CTC Decoder
+///
+/// This decoder is to be used in tandem with CTC models (e.g. Wav2Vec2). It removes the
+/// consecutive duplicates and the pad tokens, then turns the word delimiters into spaces.
+pub struct RustCtc {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl RustCtc {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&CTC) -> R,
+    {
+        if let DecoderWrapper::CTC(inner) = &*self.decoder.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the decoder must be a `CTC`")
+        }
+    }
+}
+
+impl RustCtc {
+    pub fn new(
+        pad_token: Option<String>,
+        word_delimiter_token: Option<String>,
+        cleanup: Option<bool>,
+    ) -> Self {
+        let mut ctc = CTC::default();
+
+        if let Some(pad_token) = pad_token {
+            ctc.pad_token = pad_token;
+        }
+        if let Some(word_delimiter_token) = word_delimiter_token {
+            ctc.word_delimiter_token = word_delimiter_token;
+        }
+        if let Some(cleanup) = cleanup {
+            ctc.cleanup = cleanup;
+        }
+
+        Self {
+            decoder: Arc::new(RwLock::new(ctc.into())),
+        }
+    }
+
+    pub fn get_pad_token(&self) -> String {
+        self.with_subtype(|ctc| ctc.pad_token.clone())
+    }
+
+    pub fn get_word_delimiter_token(&self) -> String {
+        self.with_subtype(|ctc| ctc.word_delimiter_token.clone())
+    }
+
+    pub fn get_cleanup(&self) -> bool {
+        self.with_subtype(|ctc| ctc.cleanup)
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this CTC decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// Replace Decoder
+///
+/// Replaces every occurrence of the pattern in each token with the given content.
+pub struct RustReplaceDecoder {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl RustReplaceDecoder {
+    pub fn new(pattern: RustPattern, content: String) -> Result<Self> {
+        let replace = Replace::new(pattern, content)?;
+
+        Ok(Self {
+            decoder: Arc::new(RwLock::new(replace.into())),
+        })
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this Replace decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// Fuse Decoder
+///
+/// Fuse simply fuses every token into a single string. This is usually the last decoding
+/// step anyway, but this decoder exists in case some decoders need to happen after that step.
+pub struct RustFuse {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl Default for RustFuse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustFuse {
+    pub fn new() -> Self {
+        Self {
+            decoder: Arc::new(RwLock::new(Fuse::new().into())),
+        }
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this Fuse decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// Strip Decoder
+///
+/// Strips `start` occurrences of the content from the left of each token, and `stop`
+/// occurrences from its right.
+pub struct RustStripDecoder {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl RustStripDecoder {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Strip) -> R,
+    {
+        if let DecoderWrapper::Strip(inner) = &*self.decoder.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the decoder must be a `Strip`")
+        }
+    }
+}
+
+impl RustStripDecoder {
+    pub fn new(
+        content: Option<String>,
+        start: Option<RustUSize>,
+        stop: Option<RustUSize>,
+    ) -> Result<Self> {
+        let content = match content {
+            Some(content) => single_char(&content, "content")?,
+            None => ' ',
+        };
+        let strip = Strip::new(content, start.unwrap_or(0), stop.unwrap_or(0));
+
+        Ok(Self {
+            decoder: Arc::new(RwLock::new(strip.into())),
+        })
+    }
+
+    pub fn get_content(&self) -> String {
+        self.with_subtype(|strip| strip.content.to_string())
+    }
+
+    pub fn get_start(&self) -> RustUSize {
+        self.with_subtype(|strip| strip.start)
+    }
+
+    pub fn get_stop(&self) -> RustUSize {
+        self.with_subtype(|strip| strip.stop)
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this Strip decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// ByteFallback Decoder
+///
+/// ByteFallback is a simple trick which converts tokens looking like `<0x61>` to pure bytes,
+/// and attempts to make them into a string. If the tokens cannot be decoded you will get
+/// `�` instead for each inconvertable byte token.
+pub struct RustByteFallback {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl Default for RustByteFallback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustByteFallback {
+    pub fn new() -> Self {
+        Self {
+            decoder: Arc::new(RwLock::new(ByteFallback::new().into())),
+        }
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this ByteFallback decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
+
+/// Allows concatenating multiple other Decoder as a Sequence.
+/// All the decoders run in sequence in the given order.
+pub struct RustDecoderSequence {
+    decoder: Arc<RwLock<DecoderWrapper>>,
+}
+
+impl RustDecoderSequence {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Sequence) -> R,
+    {
+        if let DecoderWrapper::Sequence(inner) = &*self.decoder.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the decoder must be a `Sequence`")
+        }
+    }
+}
+
+impl RustDecoderSequence {
+    pub fn new(decoders: Vec<Arc<RustDecoder>>) -> Self {
+        let decoders = decoders
+            .iter()
+            .map(|d| d.decoder.read().unwrap().clone())
+            .collect();
+
+        Self {
+            decoder: Arc::new(RwLock::new(Sequence::new(decoders).into())),
+        }
+    }
+
+    /// Returns the decoders composing this sequence.
+    ///
+    /// Like the ones given to `new`, they are copies: modifying them doesn't affect this sequence.
+    pub fn get_decoders(&self) -> Vec<Arc<RustDecoder>> {
+        self.with_subtype(|sequence| {
+            serialized_field::<_, Vec<DecoderWrapper>>(sequence, "decoders")
+                .into_iter()
+                .map(|d| Arc::new(d.into()))
+                .collect()
+        })
+    }
+
+    /// Decode the given list of tokens to a final string
+    pub fn decode_tokens(&self, tokens: Vec<String>) -> Result<String> {
+        self.as_decoder().decode_tokens(tokens)
+    }
+
+    /// Returns a decoder handle sharing this Sequence decoder, to be given to a `RustTokenizer`.
+    pub fn as_decoder(&self) -> Arc<RustDecoder> {
+        Arc::new(RustDecoder {
+            decoder: self.decoder.clone(),
+        })
+    }
+}
@@ -1,19 +1,59 @@
+pub mod chat_templates;
+pub mod decoders;
 pub mod error;
+mod ffi;
 pub mod models;
+pub mod normalizers;
 pub mod pre_tokenizers;
+pub mod processors;
 pub mod tokenizer;
 pub mod trainers;
 mod utils;
+pub use crate::chat_templates::{RustChatEncoding, RustChatMessage, RustChatTemplate};
+pub use crate::decoders::{
+    RustBpeDecoder, RustByteFallback, RustByteLevelDecoder, RustCtc, RustDecoder,
+    RustDecoderSequence, RustFuse, RustMetaspaceDecoder, RustReplaceDecoder, RustStripDecoder,
+    RustWordPieceDecoder,
+};
 pub use crate::error::TokenizersError;
+pub use crate::ffi::uniffi_reexport_hack;
 pub use crate::models::bpe::{
     bpe_read_file as models_bpe_bpe_read_file, RustBpe, RustBpeReadFileReturn,
 };
-pub use crate::pre_tokenizers::{RustPreTokenizedString, RustWhitespace};
-pub use crate::tokenizer::{RustAddedToken, RustEncoding, RustInputSequence, RustTokenizer};
-pub use crate::trainers::RustBpeTrainer;
-pub use crate::utils::{RustMerges, RustOffsets, RustUSize, RustVocab};
-use uniffi_macros;
-
-uniffi_macros::include_scaffolding!("lib");
+pub use crate::models::unigram::{RustUnigram, RustUnigramPiece, RustUnigramSegmentation};
+pub use crate::models::wordlevel::{
+    wordlevel_read_file as models_wordlevel_wordlevel_read_file, RustWordLevel,
+};
+pub use crate::models::wordpiece::{
+    wordpiece_read_file as models_wordpiece_wordpiece_read_file, RustWordPiece,
+};
+pub use crate::models::RustModel;
+pub use crate::normalizers::{
+    RustBertNormalizer, RustLowercase, RustNFC, RustNFD, RustNFKC, RustNFKD, RustNormalizer,
+    RustNormalizerSequence, RustPrecompiled, RustPrepend, RustReplace, RustStrip, RustStripAccents,
+};
+pub use crate::pre_tokenizers::{
+    byte_level_alphabet as pre_tokenizers_byte_level_alphabet, RustBertPreTokenizer, RustByteLevel,
+    RustCharDelimiterSplit, RustDigits, RustMetaspace, RustPreTokenizedSplit,
+    RustPreTokenizedString, RustPreTokenizer, RustPreTokenizerSequence, RustPunctuation, RustSplit,
+    RustUnicodeScripts, RustWhitespace, RustWhitespaceSplit,
+};
+pub use crate::processors::{
+    RustBertProcessing, RustByteLevelProcessor, RustPostProcessor, RustPostProcessorSequence,
+    RustRobertaProcessing, RustSpecialToken, RustTemplateProcessing, RustTokenId,
+};
+pub use crate::tokenizer::{
+    RustAddedToken, RustBatchBuffer, RustBufferDType, RustDecodeStream, RustDecodedPiece,
+    RustEncodeInput, RustEncoding, RustInputSequence, RustOffsetType, RustStopMatch,
+    RustStopSequenceMatcher, RustTokenizer,
+};
+pub use crate::trainers::{
+    RustBpeTrainer, RustTrainer, RustUnigramTrainer, RustWordLevelTrainer, RustWordPieceTrainer,
+};
+pub use crate::utils::{
+    RustMerges, RustOffsets, RustPaddingDirection, RustPaddingParams, RustPaddingStrategy,
+    RustPattern, RustSplitDelimiterBehavior, RustTruncationDirection, RustTruncationParams,
+    RustTruncationStrategy, RustUSize, RustVocab,
+};
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
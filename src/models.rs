@@ -1 +1,145 @@
 pub mod bpe;
+pub mod unigram;
+pub mod wordlevel;
+pub mod wordpiece;
+
+use crate::error::Result;
+use crate::models::unigram::UnigramIndex;
+use crate::utils::RustVocab;
+use crate::{RustBpe, RustTrainer, RustUnigram, RustWordLevel, RustWordPiece};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tk::ModelWrapper;
+use tokenizers as tk;
+
+/// Base class for all models
+///
+/// The model represents the actual tokenization algorithm. This is the part that
+/// will contain and manage the learned vocabulary.
+///
+/// This class cannot be constructed directly. Please use one of the concrete models
+/// and convert it with `as_model`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustModel {
+    #[serde(flatten)]
+    pub(crate) model: Arc<RwLock<ModelWrapper>>,
+    /// The index of the vocab when the model is a Unigram, shared by its handles.
+    #[serde(skip)]
+    pub(crate) unigram_index: UnigramIndex,
+}
+
+impl From<ModelWrapper> for RustModel {
+    fn from(model: ModelWrapper) -> Self {
+        Self {
+            model: Arc::new(RwLock::new(model)),
+            unigram_index: Default::default(),
+        }
+    }
+}
+
+impl tk::Model for RustModel {
+    type Trainer = RustTrainer;
+
+    fn tokenize(&self, sequence: &str) -> tk::Result<Vec<tk::Token>> {
+        self.model.read().unwrap().tokenize(sequence)
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.model.read().unwrap().token_to_id(token)
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.model.read().unwrap().id_to_token(id)
+    }
+
+    fn get_vocab(&self) -> std::collections::HashMap<String, u32> {
+        self.model.read().unwrap().get_vocab()
+    }
+
+    fn get_vocab_size(&self) -> usize {
+        self.model.read().unwrap().get_vocab_size()
+    }
+
+    fn save(
+        &self,
+        folder: &std::path::Path,
+        prefix: Option<&str>,
+    ) -> tk::Result<Vec<std::path::PathBuf>> {
+        self.model.read().unwrap().save(folder, prefix)
+    }
+
+    fn get_trainer(&self) -> <Self as tk::Model>::Trainer {
+        self.model.read().unwrap().get_trainer().into()
+    }
+}
+
+// Export
+impl RustModel {
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        tk::Model::token_to_id(self, token)
+    }
+
+    pub fn id_to_token(&self, id: u32) -> Option<String> {
+        tk::Model::id_to_token(self, id)
+    }
+
+    pub fn get_vocab(&self) -> RustVocab {
+        tk::Model::get_vocab(self)
+    }
+
+    pub fn get_vocab_size(&self) -> usize {
+        tk::Model::get_vocab_size(self)
+    }
+
+    /// Save the current model files in the given folder, using the given prefix
+    /// for the various files that will get created. Returns the list of saved files.
+    pub fn save(&self, folder: &str, prefix: Option<String>) -> Result<Vec<String>> {
+        let saved = tk::Model::save(self, std::path::Path::new(folder), prefix.as_deref())?;
+
+        Ok(saved
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    /// Returns the concrete BPE handle sharing this model, if this model is a BPE.
+    pub fn as_bpe(&self) -> Option<Arc<RustBpe>> {
+        match *self.model.read().unwrap() {
+            ModelWrapper::BPE(_) => Some(Arc::new(RustBpe {
+                model: self.model.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete WordPiece handle sharing this model, if this model is a WordPiece.
+    pub fn as_wordpiece(&self) -> Option<Arc<RustWordPiece>> {
+        match *self.model.read().unwrap() {
+            ModelWrapper::WordPiece(_) => Some(Arc::new(RustWordPiece {
+                model: self.model.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Unigram handle sharing this model, if this model is a Unigram.
+    pub fn as_unigram(&self) -> Option<Arc<RustUnigram>> {
+        match *self.model.read().unwrap() {
+            ModelWrapper::Unigram(_) => Some(Arc::new(RustUnigram::with_model(
+                self.model.clone(),
+                self.unigram_index.clone(),
+            ))),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete WordLevel handle sharing this model, if this model is a WordLevel.
+    pub fn as_wordlevel(&self) -> Option<Arc<RustWordLevel>> {
+        match *self.model.read().unwrap() {
+            ModelWrapper::WordLevel(_) => Some(Arc::new(RustWordLevel {
+                model: self.model.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::error::{Result, TokenizersError};
 use crate::utils::{RustMerges, RustVocab};
-use crate::RustBpeTrainer;
+use crate::RustModel;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 use tk::models::bpe::BPE;
@@ -21,61 +21,13 @@ impl RustBpe {
         if let ModelWrapper::BPE(bpe) = self.model.read().as_deref().unwrap() {
             callback(bpe)
         } else {
-            panic!()
+            unreachable!("the model must be a `BPE`")
         }
     }
-
-    pub(crate) fn with_subtype_mut<F, R>(&self, callback: F) -> R
-    where
-        F: FnOnce(&mut BPE) -> R,
-    {
-        let mut m = self.model.write().unwrap();
-
-        if let ModelWrapper::BPE(b) = &mut *m {
-            callback(b)
-        } else {
-            panic!()
-        }
-    }
-}
-
-impl tk::Model for RustBpe {
-    type Trainer = RustBpeTrainer;
-
-    fn tokenize(&self, sequence: &str) -> tk::Result<Vec<tk::Token>> {
-        self.model.read().unwrap().tokenize(sequence)
-    }
-
-    fn token_to_id(&self, token: &str) -> Option<u32> {
-        self.model.read().unwrap().token_to_id(token)
-    }
-
-    fn id_to_token(&self, id: u32) -> Option<String> {
-        self.model.read().unwrap().id_to_token(id)
-    }
-
-    fn get_vocab(&self) -> std::collections::HashMap<String, u32> {
-        self.model.read().unwrap().get_vocab()
-    }
-
-    fn get_vocab_size(&self) -> usize {
-        self.model.read().unwrap().get_vocab_size()
-    }
-
-    fn save(
-        &self,
-        folder: &std::path::Path,
-        prefix: Option<&str>,
-    ) -> tk::Result<Vec<std::path::PathBuf>> {
-        self.model.read().unwrap().save(folder, prefix)
-    }
-
-    fn get_trainer(&self) -> <Self as tk::Model>::Trainer {
-        self.model.read().unwrap().get_trainer().into()
-    }
 }
 
 impl RustBpe {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         vocab: Option<RustVocab>,
         merges: Option<RustMerges>,
@@ -103,7 +55,7 @@ impl RustBpe {
             builder = builder.vocab_and_merges(vocab, merges);
         }
         if let (Some(vocab_file), Some(merges_file)) = (vocab_file, merges_file) {
-            builder = builder.files(vocab_file.into(), merges_file.into());
+            builder = builder.files(vocab_file, merges_file);
         }
         if let Some(cache_capacity) = cache_capacity {
             builder = builder.cache_capacity(cache_capacity);
@@ -135,6 +87,14 @@ impl RustBpe {
     pub fn get_unk_token(&self) -> Option<String> {
         self.with_subtype(|bpe| bpe.get_unk_token().clone())
     }
+
+    /// Returns a model handle sharing this BPE, to be given to a `RustTokenizer`.
+    pub fn as_model(&self) -> Arc<RustModel> {
+        Arc::new(RustModel {
+            model: self.model.clone(),
+            unigram_index: Default::default(),
+        })
+    }
 }
 
 // Associated functions
@@ -149,8 +109,8 @@ pub fn bpe_read_file(vocab: &str, merges: &str) -> Result<RustBpeReadFileReturn>
         TokenizersError::Exception(format!("Error while reading vocab & merges files: {}", e))
     })?;
 
-    return Ok(RustBpeReadFileReturn {
+    Ok(RustBpeReadFileReturn {
         vocab: vocab_and_merges.0,
         merges: vocab_and_merges.1,
-    });
+    })
 }
@@ -0,0 +1,289 @@
+use crate::error::{Result, TokenizersError};
+use crate::utils::serialized_field;
+use crate::RustModel;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, RwLock};
+use tk::models::unigram::{Lattice, Unigram};
+use tk::ModelWrapper;
+use tokenizers as tk;
+
+/// Same penalty as upstream's lattice population for characters not covered by the vocab.
+const UNK_PENALTY: f64 = 10.0;
+
+/// A piece of the Unigram vocabulary, along with its score (a log-probability).
+#[derive(Debug, Clone)]
+pub struct RustUnigramPiece {
+    pub piece: String,
+    pub score: f64,
+}
+
+/// One of the n-best segmentations of a sentence, along with its log-probability.
+#[derive(Debug, Clone)]
+pub struct RustUnigramSegmentation {
+    pub tokens: Vec<String>,
+    pub ids: Vec<u32>,
+    pub log_prob: f64,
+}
+
+/// What `nbest` and `get_score` need from the vocab, which upstream only exposes through
+/// an iterator.
+struct PieceIndex {
+    scores: Vec<f64>,
+    max_piece_len: usize,
+    unk_id: Option<usize>,
+}
+
+impl PieceIndex {
+    fn new(unigram: &Unigram) -> Self {
+        Self {
+            scores: unigram.iter().map(|(_, score)| *score).collect(),
+            max_piece_len: unigram
+                .iter()
+                .map(|(piece, _)| piece.len())
+                .max()
+                .unwrap_or(0),
+            unk_id: serialized_field(unigram, "unk_id"),
+        }
+    }
+}
+
+/// The `PieceIndex` of a model, built on first use. It is shared by all the handles of the
+/// model, and must be cleared whenever the model changes in place, i.e. when it is trained.
+#[derive(Clone, Default)]
+pub(crate) struct UnigramIndex(Arc<Mutex<Option<Arc<PieceIndex>>>>);
+
+impl UnigramIndex {
+    fn get(&self, unigram: &Unigram) -> Arc<PieceIndex> {
+        self.0
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| Arc::new(PieceIndex::new(unigram)))
+            .clone()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.0.lock().unwrap().take();
+    }
+}
+
+/// An implementation of the Unigram algorithm
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustUnigram {
+    #[serde(flatten)]
+    pub(crate) model: Arc<RwLock<ModelWrapper>>,
+    #[serde(skip)]
+    index: UnigramIndex,
+}
+
+impl RustUnigram {
+    pub(crate) fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Unigram) -> R,
+    {
+        if let ModelWrapper::Unigram(unigram) = self.model.read().as_deref().unwrap() {
+            callback(unigram)
+        } else {
+            unreachable!("the model must be a `Unigram`")
+        }
+    }
+
+    pub(crate) fn with_model(model: Arc<RwLock<ModelWrapper>>, index: UnigramIndex) -> Self {
+        Self { model, index }
+    }
+}
+
+impl RustUnigram {
+    pub fn new(
+        vocab: Option<Vec<RustUnigramPiece>>,
+        unk_id: Option<usize>,
+        byte_fallback: Option<bool>,
+    ) -> Result<Self> {
+        let unigram = match (vocab, unk_id, byte_fallback) {
+            (Some(vocab), unk_id, byte_fallback) => {
+                let vocab = vocab.into_iter().map(|p| (p.piece, p.score)).collect();
+
+                Unigram::from(vocab, unk_id, byte_fallback.unwrap_or(false)).map_err(|e| {
+                    TokenizersError::Exception(format!("Error while loading Unigram: {}", e))
+                })?
+            }
+            (None, None, None) => Unigram::default(),
+            _ => {
+                return Err(TokenizersError::ValueError(
+                    "`unk_id` and `byte_fallback` require `vocab`".into(),
+                ))
+            }
+        };
+
+        Ok(Self::with_model(
+            Arc::new(RwLock::new(ModelWrapper::Unigram(unigram))),
+            UnigramIndex::default(),
+        ))
+    }
+
+    /// The vocabulary as a list of pieces with their scores, ordered by id.
+    pub fn get_pieces(&self) -> Vec<RustUnigramPiece> {
+        self.with_subtype(|unigram| {
+            unigram
+                .iter()
+                .map(|(piece, score)| RustUnigramPiece {
+                    piece: piece.clone(),
+                    score: *score,
+                })
+                .collect()
+        })
+    }
+
+    pub fn get_score(&self, piece: &str) -> Option<f64> {
+        self.with_subtype(|unigram| {
+            let index = self.index.get(unigram);
+            tk::Model::token_to_id(unigram, piece).map(|id| index.scores[id as usize])
+        })
+    }
+
+    pub fn get_unk_id(&self) -> Option<usize> {
+        self.with_subtype(|unigram| serialized_field(unigram, "unk_id"))
+    }
+
+    pub fn get_byte_fallback(&self) -> bool {
+        self.with_subtype(|unigram| unigram.byte_fallback())
+    }
+
+    /// Returns the `n` most likely segmentations of the given sentence, best first.
+    ///
+    /// Unlike `tokenize`, the sentence is segmented as a whole, so it should be given
+    /// already normalized and pre-tokenized the way the tokenizer would. Unknown pieces are
+    /// reported as `tokenize` would: split into bytes with `byte_fallback`, or as the unknown
+    /// token otherwise.
+    pub fn nbest(&self, sentence: &str, n: usize) -> Result<Vec<RustUnigramSegmentation>> {
+        self.with_subtype(|unigram| {
+            let index = self.index.get(unigram);
+            let unk_score = unigram.min_score - UNK_PENALTY;
+            let vocab_size = index.scores.len();
+            let mut lattice = Lattice::from(sentence, vocab_size + 1, vocab_size + 2);
+
+            // Same as upstream's `populate_nodes`, which is private
+            for (begin, c) in sentence.char_indices() {
+                let mut has_single_node = false;
+
+                let ends = sentence[begin..]
+                    .char_indices()
+                    .skip(1)
+                    .map(|(i, _)| begin + i)
+                    .chain(std::iter::once(sentence.len()));
+
+                for end in ends.take_while(|end| end - begin <= index.max_piece_len) {
+                    if let Some(id) = tk::Model::token_to_id(unigram, &sentence[begin..end]) {
+                        lattice.insert(begin, end - begin, index.scores[id as usize], id as usize);
+                        has_single_node |= end - begin == c.len_utf8();
+                    }
+                }
+                if !has_single_node {
+                    if let Some(unk_id) = index.unk_id {
+                        lattice.insert(begin, c.len_utf8(), unk_score, unk_id);
+                    }
+                }
+            }
+
+            let paths = lattice.nbest(n);
+            if n > 0 && !sentence.is_empty() && paths.iter().all(Vec::is_empty) {
+                return Err(TokenizersError::ValueError(format!(
+                    "{:?} can't be segmented without `unk_id`",
+                    sentence
+                )));
+            }
+
+            Ok(paths
+                .iter()
+                .map(|nodes| {
+                    let pieces: Vec<String> = nodes
+                        .iter()
+                        .map(|node| lattice.piece(&node.borrow()))
+                        .collect();
+                    segmentation(unigram, &index, unk_score, &pieces)
+                })
+                .collect())
+        })
+    }
+
+    /// Returns a model handle sharing this Unigram, to be given to a `RustTokenizer`.
+    pub fn as_model(&self) -> Arc<RustModel> {
+        Arc::new(RustModel {
+            model: self.model.clone(),
+            unigram_index: self.index.clone(),
+        })
+    }
+}
+
+/// Turns a path of the lattice into tokens the way `tokenize` does: consecutive unknown
+/// pieces are fused, then split into bytes if the model falls back to them.
+fn segmentation(
+    unigram: &Unigram,
+    index: &PieceIndex,
+    unk_score: f64,
+    pieces: &[String],
+) -> RustUnigramSegmentation {
+    let mut segmentation = RustUnigramSegmentation {
+        tokens: vec![],
+        ids: vec![],
+        log_prob: 0.0,
+    };
+    let mut unknown = String::new();
+
+    for piece in pieces {
+        match tk::Model::token_to_id(unigram, piece) {
+            Some(id) => {
+                push_unknown(unigram, index, &mut unknown, &mut segmentation);
+                segmentation.tokens.push(piece.clone());
+                segmentation.ids.push(id);
+                segmentation.log_prob += index.scores[id as usize];
+            }
+            None => {
+                unknown.push_str(piece);
+                segmentation.log_prob += unk_score;
+            }
+        }
+    }
+    push_unknown(unigram, index, &mut unknown, &mut segmentation);
+
+    segmentation
+}
+
+fn push_unknown(
+    unigram: &Unigram,
+    index: &PieceIndex,
+    unknown: &mut String,
+    segmentation: &mut RustUnigramSegmentation,
+) {
+    if unknown.is_empty() {
+        return;
+    }
+
+    let bytes: Option<Vec<(String, u32)>> = if unigram.byte_fallback() {
+        unknown
+            .bytes()
+            .map(|byte| {
+                let token = format!("<0x{:02X}>", byte);
+                tk::Model::token_to_id(unigram, &token).map(|id| (token, id))
+            })
+            .collect()
+    } else {
+        None
+    };
+
+    match bytes {
+        Some(bytes) => {
+            for (token, id) in bytes {
+                segmentation.tokens.push(token);
+                segmentation.ids.push(id);
+            }
+        }
+        None => {
+            // Unknown pieces are only inserted in the lattice when there is an `unk_id`
+            let unk_id = index.unk_id.unwrap_or_default() as u32;
+            let unk_token = tk::Model::id_to_token(unigram, unk_id).unwrap_or_default();
+            segmentation.tokens.push(unk_token);
+            segmentation.ids.push(unk_id);
+        }
+    }
+    unknown.clear();
+}
@@ -0,0 +1,83 @@
+use crate::error::{Result, TokenizersError};
+use crate::utils::RustVocab;
+use crate::RustModel;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tk::models::wordlevel::WordLevel;
+use tk::ModelWrapper;
+use tokenizers as tk;
+
+/// An implementation of the WordLevel algorithm
+///
+/// Most simple tokenizer model based on mapping tokens to their corresponding id.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustWordLevel {
+    #[serde(flatten)]
+    pub(crate) model: Arc<RwLock<ModelWrapper>>,
+}
+
+impl RustWordLevel {
+    pub(crate) fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&WordLevel) -> R,
+    {
+        if let ModelWrapper::WordLevel(wordlevel) = self.model.read().as_deref().unwrap() {
+            callback(wordlevel)
+        } else {
+            unreachable!("the model must be a `WordLevel`")
+        }
+    }
+}
+
+impl RustWordLevel {
+    pub fn new(
+        vocab: Option<RustVocab>,
+        vocab_file: Option<String>,
+        unk_token: Option<String>,
+    ) -> Result<Self> {
+        if vocab.is_some() && vocab_file.is_some() {
+            return Err(TokenizersError::ValueError(
+                "`vocab` and `vocab_file` cannot be both specified".into(),
+            ));
+        }
+
+        let mut builder = WordLevel::builder();
+
+        if let Some(vocab) = vocab {
+            builder = builder.vocab(vocab);
+        }
+        if let Some(vocab_file) = vocab_file {
+            builder = builder.files(vocab_file);
+        }
+        if let Some(unk_token) = unk_token {
+            builder = builder.unk_token(unk_token);
+        }
+
+        let wordlevel = builder.build().map_err(|e| {
+            TokenizersError::Exception(format!("Error while initializing WordLevel: {}", e))
+        })?;
+
+        Ok(Self {
+            model: Arc::new(RwLock::new(ModelWrapper::WordLevel(wordlevel))),
+        })
+    }
+
+    pub fn get_unk_token(&self) -> String {
+        self.with_subtype(|wordlevel| wordlevel.unk_token.clone())
+    }
+
+    /// Returns a model handle sharing this WordLevel, to be given to a `RustTokenizer`.
+    pub fn as_model(&self) -> Arc<RustModel> {
+        Arc::new(RustModel {
+            model: self.model.clone(),
+            unigram_index: Default::default(),
+        })
+    }
+}
+
+// Associated functions
+pub fn wordlevel_read_file(vocab: &str) -> Result<RustVocab> {
+    WordLevel::read_file(vocab).map_err(|e| {
+        TokenizersError::Exception(format!("Error while reading WordLevel file: {}", e))
+    })
+}
@@ -0,0 +1,97 @@
+use crate::error::{Result, TokenizersError};
+use crate::utils::RustVocab;
+use crate::RustModel;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tk::models::wordpiece::WordPiece;
+use tk::ModelWrapper;
+use tokenizers as tk;
+
+/// An implementation of the WordPiece algorithm
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustWordPiece {
+    #[serde(flatten)]
+    pub(crate) model: Arc<RwLock<ModelWrapper>>,
+}
+
+impl RustWordPiece {
+    pub(crate) fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&WordPiece) -> R,
+    {
+        if let ModelWrapper::WordPiece(wordpiece) = self.model.read().as_deref().unwrap() {
+            callback(wordpiece)
+        } else {
+            unreachable!("the model must be a `WordPiece`")
+        }
+    }
+}
+
+impl RustWordPiece {
+    pub fn new(
+        vocab: Option<RustVocab>,
+        vocab_file: Option<String>,
+        unk_token: Option<String>,
+        max_input_chars_per_word: Option<usize>,
+        continuing_subword_prefix: Option<String>,
+    ) -> Result<Self> {
+        if vocab.is_some() && vocab_file.is_some() {
+            return Err(TokenizersError::ValueError(
+                "`vocab` and `vocab_file` cannot be both specified".into(),
+            ));
+        }
+
+        let mut builder = WordPiece::builder();
+
+        if let Some(vocab) = vocab {
+            builder = builder.vocab(vocab);
+        }
+        if let Some(vocab_file) = vocab_file {
+            builder = builder.files(vocab_file);
+        }
+        if let Some(unk_token) = unk_token {
+            builder = builder.unk_token(unk_token);
+        }
+        if let Some(max_input_chars_per_word) = max_input_chars_per_word {
+            builder = builder.max_input_chars_per_word(max_input_chars_per_word);
+        }
+        if let Some(continuing_subword_prefix) = continuing_subword_prefix {
+            builder = builder.continuing_subword_prefix(continuing_subword_prefix);
+        }
+
+        let wordpiece = builder.build().map_err(|e| {
+            TokenizersError::Exception(format!("Error while initializing WordPiece: {}", e))
+        })?;
+
+        Ok(Self {
+            model: Arc::new(RwLock::new(ModelWrapper::WordPiece(wordpiece))),
+        })
+    }
+
+    pub fn get_unk_token(&self) -> String {
+        self.with_subtype(|wordpiece| wordpiece.unk_token.clone())
+    }
+
+    pub fn get_continuing_subword_prefix(&self) -> String {
+        self.with_subtype(|wordpiece| wordpiece.continuing_subword_prefix.clone())
+    }
+
+    pub fn get_max_input_chars_per_word(&self) -> usize {
+        self.with_subtype(|wordpiece| wordpiece.max_input_chars_per_word)
+    }
+
+    /// Returns a model handle sharing this WordPiece, to be given to a `RustTokenizer`.
+    pub fn as_model(&self) -> Arc<RustModel> {
+        Arc::new(RustModel {
+            model: self.model.clone(),
+            unigram_index: Default::default(),
+        })
+    }
+}
+
+// Associated functions
+pub fn wordpiece_read_file(vocab: &str) -> Result<RustVocab> {
+    WordPiece::read_file(vocab).map_err(|e| {
+        TokenizersError::Exception(format!("Error while reading WordPiece file: {}", e))
+    })
+}
@@ -0,0 +1,558 @@
+use crate::error::{Result, TokenizersError};
+use crate::utils::RustPattern;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tk::normalizers::{
+    BertNormalizer, Lowercase, Precompiled, Prepend, Replace, Sequence, Strip, StripAccents, NFC,
+    NFD, NFKC, NFKD,
+};
+use tk::{NormalizedString, Normalizer, NormalizerWrapper};
+use tokenizers as tk;
+
+/// Base class for all normalizers
+///
+/// This class is not supposed to be instantiated directly. Instead, any implementation of a
+/// Normalizer will return an instance of this class when converted with `as_normalizer`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustNormalizer {
+    #[serde(flatten)]
+    pub(crate) normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl From<NormalizerWrapper> for RustNormalizer {
+    fn from(normalizer: NormalizerWrapper) -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(normalizer)),
+        }
+    }
+}
+
+impl Normalizer for RustNormalizer {
+    fn normalize(&self, normalized: &mut NormalizedString) -> tk::Result<()> {
+        self.normalizer.read().unwrap().normalize(normalized)
+    }
+}
+
+impl RustNormalizer {
+    /// Normalize the given string
+    ///
+    /// This method provides a way to visualize the effect of a Normalizer but it does not
+    /// keep track of the alignment information.
+    pub fn normalize_str(&self, sequence: &str) -> Result<String> {
+        let mut normalized = NormalizedString::from(sequence);
+
+        self.normalizer
+            .read()
+            .unwrap()
+            .normalize(&mut normalized)
+            .map_err(|e| TokenizersError::Exception(format!("Error while normalizing: {}", e)))?;
+
+        Ok(normalized.get().to_owned())
+    }
+
+    /// Returns the concrete BertNormalizer handle sharing this normalizer, if any.
+    pub fn as_bert_normalizer(&self) -> Option<Arc<RustBertNormalizer>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::BertNormalizer(_) => Some(Arc::new(RustBertNormalizer {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Precompiled handle sharing this normalizer, if any.
+    pub fn as_precompiled(&self) -> Option<Arc<RustPrecompiled>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::Precompiled(_) => Some(Arc::new(RustPrecompiled {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete NFC handle sharing this normalizer, if any.
+    pub fn as_nfc(&self) -> Option<Arc<RustNFC>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::NFC(_) => Some(Arc::new(RustNFC {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete NFD handle sharing this normalizer, if any.
+    pub fn as_nfd(&self) -> Option<Arc<RustNFD>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::NFD(_) => Some(Arc::new(RustNFD {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete NFKC handle sharing this normalizer, if any.
+    pub fn as_nfkc(&self) -> Option<Arc<RustNFKC>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::NFKC(_) => Some(Arc::new(RustNFKC {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete NFKD handle sharing this normalizer, if any.
+    pub fn as_nfkd(&self) -> Option<Arc<RustNFKD>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::NFKD(_) => Some(Arc::new(RustNFKD {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Lowercase handle sharing this normalizer, if any.
+    pub fn as_lowercase(&self) -> Option<Arc<RustLowercase>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::Lowercase(_) => Some(Arc::new(RustLowercase {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Strip handle sharing this normalizer, if any.
+    pub fn as_strip(&self) -> Option<Arc<RustStrip>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::StripNormalizer(_) => Some(Arc::new(RustStrip {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete StripAccents handle sharing this normalizer, if any.
+    pub fn as_strip_accents(&self) -> Option<Arc<RustStripAccents>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::StripAccents(_) => Some(Arc::new(RustStripAccents {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Replace handle sharing this normalizer, if any.
+    pub fn as_replace(&self) -> Option<Arc<RustReplace>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::Replace(_) => Some(Arc::new(RustReplace {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Prepend handle sharing this normalizer, if any.
+    pub fn as_prepend(&self) -> Option<Arc<RustPrepend>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::Prepend(_) => Some(Arc::new(RustPrepend {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Sequence handle sharing this normalizer, if any.
+    pub fn as_sequence(&self) -> Option<Arc<RustNormalizerSequence>> {
+        match *self.normalizer.read().unwrap() {
+            NormalizerWrapper::Sequence(_) => Some(Arc::new(RustNormalizerSequence {
+                normalizer: self.normalizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// BertNormalizer
+///
+/// Takes care of normalizing raw text before giving it to a Bert model.
+/// This includes cleaning the text, handling accents, chinese chars and lowercasing.
+pub struct RustBertNormalizer {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl RustBertNormalizer {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&BertNormalizer) -> R,
+    {
+        if let NormalizerWrapper::BertNormalizer(bert) = &*self.normalizer.read().unwrap() {
+            callback(bert)
+        } else {
+            unreachable!("the normalizer must be a `BertNormalizer`")
+        }
+    }
+}
+
+impl RustBertNormalizer {
+    pub fn new(
+        clean_text: Option<bool>,
+        handle_chinese_chars: Option<bool>,
+        strip_accents: Option<bool>,
+        lowercase: Option<bool>,
+    ) -> Self {
+        let bert = BertNormalizer::new(
+            clean_text.unwrap_or(true),
+            handle_chinese_chars.unwrap_or(true),
+            strip_accents,
+            lowercase.unwrap_or(true),
+        );
+
+        Self {
+            normalizer: Arc::new(RwLock::new(bert.into())),
+        }
+    }
+
+    pub fn get_clean_text(&self) -> bool {
+        self.with_subtype(|bert| bert.clean_text)
+    }
+
+    pub fn get_handle_chinese_chars(&self) -> bool {
+        self.with_subtype(|bert| bert.handle_chinese_chars)
+    }
+
+    /// When unset, accents are stripped only if `lowercase` is enabled (as in the original BERT).
+    pub fn get_strip_accents(&self) -> Option<bool> {
+        self.with_subtype(|bert| bert.strip_accents)
+    }
+
+    pub fn get_lowercase(&self) -> bool {
+        self.with_subtype(|bert| bert.lowercase)
+    }
+
+    /// Returns a normalizer handle sharing this BertNormalizer, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// Precompiled normalizer
+///
+/// Reproduces the normalization of a SentencePiece model from its precompiled charsmap.
+pub struct RustPrecompiled {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl RustPrecompiled {
+    pub fn new(precompiled_charsmap: Vec<u8>) -> Result<Self> {
+        let precompiled = Precompiled::from(&precompiled_charsmap).map_err(|e| {
+            TokenizersError::Exception(format!(
+                "Error while attempting to build Precompiled normalizer: {}",
+                e
+            ))
+        })?;
+
+        Ok(Self {
+            normalizer: Arc::new(RwLock::new(precompiled.into())),
+        })
+    }
+
+    /// Returns a normalizer handle sharing this Precompiled, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// NFC Unicode Normalizer
+pub struct RustNFC {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl Default for RustNFC {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustNFC {
+    pub fn new() -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(NFC.into())),
+        }
+    }
+
+    /// Returns a normalizer handle sharing this NFC, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// NFD Unicode Normalizer
+pub struct RustNFD {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl Default for RustNFD {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustNFD {
+    pub fn new() -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(NFD.into())),
+        }
+    }
+
+    /// Returns a normalizer handle sharing this NFD, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// NFKC Unicode Normalizer
+pub struct RustNFKC {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl Default for RustNFKC {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustNFKC {
+    pub fn new() -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(NFKC.into())),
+        }
+    }
+
+    /// Returns a normalizer handle sharing this NFKC, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// NFKD Unicode Normalizer
+pub struct RustNFKD {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl Default for RustNFKD {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustNFKD {
+    pub fn new() -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(NFKD.into())),
+        }
+    }
+
+    /// Returns a normalizer handle sharing this NFKD, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// Lowercase Normalizer
+pub struct RustLowercase {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl Default for RustLowercase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustLowercase {
+    pub fn new() -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(Lowercase.into())),
+        }
+    }
+
+    /// Returns a normalizer handle sharing this Lowercase, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// Strip normalizer
+///
+/// Removes the whitespaces on the left and/or right side of the input.
+pub struct RustStrip {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl RustStrip {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Strip) -> R,
+    {
+        if let NormalizerWrapper::StripNormalizer(strip) = &*self.normalizer.read().unwrap() {
+            callback(strip)
+        } else {
+            unreachable!("the normalizer must be a `StripNormalizer`")
+        }
+    }
+}
+
+impl RustStrip {
+    pub fn new(left: Option<bool>, right: Option<bool>) -> Self {
+        let strip = Strip::new(left.unwrap_or(true), right.unwrap_or(true));
+
+        Self {
+            normalizer: Arc::new(RwLock::new(strip.into())),
+        }
+    }
+
+    pub fn get_left(&self) -> bool {
+        self.with_subtype(|strip| strip.strip_left)
+    }
+
+    pub fn get_right(&self) -> bool {
+        self.with_subtype(|strip| strip.strip_right)
+    }
+
+    /// Returns a normalizer handle sharing this Strip, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// StripAccents normalizer
+///
+/// Removes the combining marks, which is mostly useful after an `NFD` normalization.
+pub struct RustStripAccents {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl Default for RustStripAccents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustStripAccents {
+    pub fn new() -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(StripAccents.into())),
+        }
+    }
+
+    /// Returns a normalizer handle sharing this StripAccents, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// Replace normalizer
+///
+/// Replaces every occurrence of the pattern, either a string or a regex, with `content`.
+pub struct RustReplace {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl RustReplace {
+    pub fn new(pattern: RustPattern, content: String) -> Result<Self> {
+        let replace = Replace::new(pattern, content)?;
+
+        Ok(Self {
+            normalizer: Arc::new(RwLock::new(replace.into())),
+        })
+    }
+
+    /// Returns a normalizer handle sharing this Replace, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// Prepend normalizer
+///
+/// Prepends the given string to the input, unless the input is empty.
+pub struct RustPrepend {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl RustPrepend {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Prepend) -> R,
+    {
+        if let NormalizerWrapper::Prepend(prepend) = &*self.normalizer.read().unwrap() {
+            callback(prepend)
+        } else {
+            unreachable!("the normalizer must be a `Prepend`")
+        }
+    }
+}
+
+impl RustPrepend {
+    pub fn new(prepend: String) -> Self {
+        Self {
+            normalizer: Arc::new(RwLock::new(Prepend::new(prepend).into())),
+        }
+    }
+
+    pub fn get_prepend(&self) -> String {
+        self.with_subtype(|prepend| prepend.prepend.clone())
+    }
+
+    /// Returns a normalizer handle sharing this Prepend, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
+
+/// Allows concatenating multiple other Normalizer as a Sequence.
+/// All the normalizers run in sequence in the given order.
+pub struct RustNormalizerSequence {
+    normalizer: Arc<RwLock<NormalizerWrapper>>,
+}
+
+impl RustNormalizerSequence {
+    pub fn new(normalizers: Vec<Arc<RustNormalizer>>) -> Self {
+        let normalizers = normalizers
+            .iter()
+            .map(|n| n.normalizer.read().unwrap().clone())
+            .collect();
+
+        Self {
+            normalizer: Arc::new(RwLock::new(Sequence::new(normalizers).into())),
+        }
+    }
+
+    /// Returns a normalizer handle sharing this Sequence, to be given to a `RustTokenizer`.
+    pub fn as_normalizer(&self) -> Arc<RustNormalizer> {
+        Arc::new(RustNormalizer {
+            normalizer: self.normalizer.clone(),
+        })
+    }
+}
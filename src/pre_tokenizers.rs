@@ -1,10 +1,22 @@
 use std::sync::{Arc, RwLock};
 
 use crate::error::{Result, TokenizersError};
-use crate::utils::RustOffsets;
+use crate::utils::{
+    serialized_field, single_char, RustOffsets, RustPattern, RustSplitDelimiterBehavior,
+};
 use serde::{Deserialize, Serialize};
+use tk::pre_tokenizers::bert::BertPreTokenizer;
+use tk::pre_tokenizers::byte_level::ByteLevel;
+use tk::pre_tokenizers::delimiter::CharDelimiterSplit;
+use tk::pre_tokenizers::digits::Digits;
+use tk::pre_tokenizers::metaspace::Metaspace;
+use tk::pre_tokenizers::punctuation::Punctuation;
+use tk::pre_tokenizers::sequence::Sequence;
+use tk::pre_tokenizers::split::{Split, SplitPattern};
+use tk::pre_tokenizers::unicode_scripts::UnicodeScripts;
+use tk::pre_tokenizers::whitespace::{Whitespace, WhitespaceSplit};
 use tk::PreTokenizerWrapper;
-use tk::{pre_tokenizers::whitespace::Whitespace, PreTokenizedString, PreTokenizer};
+use tk::{PreTokenizedString, PreTokenizer, SplitDelimiterBehavior};
 use tokenizers as tk;
 
 /// PreTokenizedString
@@ -21,7 +33,7 @@ use tokenizers as tk;
 ///
 /// - Parameters:
 ///     - sequence:
-///         The string sequence used to initialize this PreTokenizedString
+///       The string sequence used to initialize this PreTokenizedString
 pub struct RustPreTokenizedString {
     string: tk::PreTokenizedString,
 }
@@ -44,29 +56,43 @@ impl RustPreTokenizedString {
     }
 }
 
-/// This pre-tokenizer simply splits using the following regex: `\w+|[^\w\s]+`
+/// A substring produced by a pre-tokenizer, along with its offsets in the original string.
+pub struct RustPreTokenizedSplit {
+    pub content: String,
+    pub offsets: RustOffsets,
+}
+
+/// Base class for all pre-tokenizers
+///
+/// This class is not supposed to be instantiated directly. Instead, any implementation of a
+/// PreTokenizer will return an instance of this class when converted with `as_pre_tokenizer`.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct RustWhitespace {
+pub struct RustPreTokenizer {
     #[serde(flatten)]
     pub(crate) pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
 }
 
-impl PreTokenizer for RustWhitespace {
-    fn pre_tokenize(&self, normalized: &mut PreTokenizedString) -> tk::Result<()> {
-        self.pre_tokenizer.read().unwrap().pre_tokenize(normalized)
+impl From<PreTokenizerWrapper> for RustPreTokenizer {
+    fn from(pre_tokenizer: PreTokenizerWrapper) -> Self {
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(pre_tokenizer)),
+        }
     }
 }
 
-impl RustWhitespace {
-    pub fn new() -> Self {
-        Self {
-            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Whitespace(
-                Whitespace::default(),
-            ))),
-        }
+impl PreTokenizer for RustPreTokenizer {
+    fn pre_tokenize(&self, normalized: &mut PreTokenizedString) -> tk::Result<()> {
+        self.pre_tokenizer.read().unwrap().pre_tokenize(normalized)
     }
+}
 
-    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<(String, RustOffsets)>> {
+impl RustPreTokenizer {
+    /// Pre tokenize the given string
+    ///
+    /// This method provides a way to visualize the effect of a PreTokenizer but it does not
+    /// keep track of the alignment, nor does it provide all the capabilities of the
+    /// PreTokenizedString.
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
         let mut pretokenized = tk::tokenizer::PreTokenizedString::from(s);
 
         self.pre_tokenizer
@@ -80,7 +106,654 @@ impl RustWhitespace {
         Ok(pretokenized
             .get_splits(tk::OffsetReferential::Original, tk::OffsetType::Char)
             .into_iter()
-            .map(|(s, o, _)| (s.to_owned(), o))
+            .map(|(s, o, _)| RustPreTokenizedSplit {
+                content: s.to_owned(),
+                offsets: o,
+            })
             .collect())
     }
+
+    /// Returns the concrete ByteLevel handle sharing this pre-tokenizer, if any.
+    pub fn as_byte_level(&self) -> Option<Arc<RustByteLevel>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::ByteLevel(_) => Some(Arc::new(RustByteLevel {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Metaspace handle sharing this pre-tokenizer, if any.
+    pub fn as_metaspace(&self) -> Option<Arc<RustMetaspace>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::Metaspace(_) => Some(Arc::new(RustMetaspace {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Whitespace handle sharing this pre-tokenizer, if any.
+    pub fn as_whitespace(&self) -> Option<Arc<RustWhitespace>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::Whitespace(_) => Some(Arc::new(RustWhitespace {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Split handle sharing this pre-tokenizer, if any.
+    pub fn as_split(&self) -> Option<Arc<RustSplit>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::Split(_) => Some(Arc::new(RustSplit {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Punctuation handle sharing this pre-tokenizer, if any.
+    pub fn as_punctuation(&self) -> Option<Arc<RustPunctuation>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::Punctuation(_) => Some(Arc::new(RustPunctuation {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Digits handle sharing this pre-tokenizer, if any.
+    pub fn as_digits(&self) -> Option<Arc<RustDigits>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::Digits(_) => Some(Arc::new(RustDigits {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete CharDelimiterSplit handle sharing this pre-tokenizer, if any.
+    pub fn as_char_delimiter_split(&self) -> Option<Arc<RustCharDelimiterSplit>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::Delimiter(_) => Some(Arc::new(RustCharDelimiterSplit {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete WhitespaceSplit handle sharing this pre-tokenizer, if any.
+    pub fn as_whitespace_split(&self) -> Option<Arc<RustWhitespaceSplit>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::WhitespaceSplit(_) => Some(Arc::new(RustWhitespaceSplit {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete BertPreTokenizer handle sharing this pre-tokenizer, if any.
+    pub fn as_bert_pre_tokenizer(&self) -> Option<Arc<RustBertPreTokenizer>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::BertPreTokenizer(_) => Some(Arc::new(RustBertPreTokenizer {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Sequence handle sharing this pre-tokenizer, if any.
+    pub fn as_sequence(&self) -> Option<Arc<RustPreTokenizerSequence>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::Sequence(_) => Some(Arc::new(RustPreTokenizerSequence {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete UnicodeScripts handle sharing this pre-tokenizer, if any.
+    pub fn as_unicode_scripts(&self) -> Option<Arc<RustUnicodeScripts>> {
+        match *self.pre_tokenizer.read().unwrap() {
+            PreTokenizerWrapper::UnicodeScripts(_) => Some(Arc::new(RustUnicodeScripts {
+                pre_tokenizer: self.pre_tokenizer.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// This pre-tokenizer simply splits using the following regex: `\w+|[^\w\s]+`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustWhitespace {
+    #[serde(flatten)]
+    pub(crate) pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl Default for RustWhitespace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustWhitespace {
+    pub fn new() -> Self {
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Whitespace(Whitespace))),
+        }
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this Whitespace, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// ByteLevel PreTokenizer
+///
+/// This pre-tokenizer takes care of replacing all bytes of the given string
+/// with a corresponding representation, as well as splitting into words.
+pub struct RustByteLevel {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl RustByteLevel {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&ByteLevel) -> R,
+    {
+        if let PreTokenizerWrapper::ByteLevel(byte_level) = &*self.pre_tokenizer.read().unwrap() {
+            callback(byte_level)
+        } else {
+            unreachable!("the pre-tokenizer must be a `ByteLevel`")
+        }
+    }
+}
+
+impl RustByteLevel {
+    pub fn new(
+        add_prefix_space: Option<bool>,
+        trim_offsets: Option<bool>,
+        use_regex: Option<bool>,
+    ) -> Self {
+        let mut byte_level = ByteLevel::default();
+
+        if let Some(add_prefix_space) = add_prefix_space {
+            byte_level = byte_level.add_prefix_space(add_prefix_space);
+        }
+        if let Some(trim_offsets) = trim_offsets {
+            byte_level = byte_level.trim_offsets(trim_offsets);
+        }
+        if let Some(use_regex) = use_regex {
+            byte_level = byte_level.use_regex(use_regex);
+        }
+
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::ByteLevel(byte_level))),
+        }
+    }
+
+    pub fn get_add_prefix_space(&self) -> bool {
+        self.with_subtype(|byte_level| byte_level.add_prefix_space)
+    }
+
+    pub fn get_trim_offsets(&self) -> bool {
+        self.with_subtype(|byte_level| byte_level.trim_offsets)
+    }
+
+    pub fn get_use_regex(&self) -> bool {
+        self.with_subtype(|byte_level| byte_level.use_regex)
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this ByteLevel, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// Metaspace pre-tokenizer
+///
+/// This pre-tokenizer replaces any whitespace by the provided replacement character.
+/// It then tries to split on these spaces.
+pub struct RustMetaspace {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl RustMetaspace {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Metaspace) -> R,
+    {
+        if let PreTokenizerWrapper::Metaspace(metaspace) = &*self.pre_tokenizer.read().unwrap() {
+            callback(metaspace)
+        } else {
+            unreachable!("the pre-tokenizer must be a `Metaspace`")
+        }
+    }
+}
+
+impl RustMetaspace {
+    pub fn new(replacement: Option<String>, add_prefix_space: Option<bool>) -> Result<Self> {
+        let mut metaspace = Metaspace::default();
+
+        if let Some(replacement) = replacement {
+            metaspace.set_replacement(single_char(&replacement, "replacement")?);
+        }
+        if let Some(add_prefix_space) = add_prefix_space {
+            metaspace.add_prefix_space = add_prefix_space;
+        }
+
+        Ok(Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Metaspace(metaspace))),
+        })
+    }
+
+    pub fn get_replacement(&self) -> String {
+        self.with_subtype(|metaspace| metaspace.get_replacement().to_string())
+    }
+
+    pub fn get_add_prefix_space(&self) -> bool {
+        self.with_subtype(|metaspace| metaspace.add_prefix_space)
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this Metaspace, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// Split PreTokenizer
+///
+/// This versatile pre-tokenizer splits using the provided pattern and according to the
+/// provided behavior. The pattern can be inverted by making use of the invert flag.
+pub struct RustSplit {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl RustSplit {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Split) -> R,
+    {
+        if let PreTokenizerWrapper::Split(inner) = &*self.pre_tokenizer.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the pre-tokenizer must be a `Split`")
+        }
+    }
+}
+
+impl RustSplit {
+    pub fn new(
+        pattern: RustPattern,
+        behavior: RustSplitDelimiterBehavior,
+        invert: Option<bool>,
+    ) -> Result<Self> {
+        let split = Split::new(
+            SplitPattern::from(pattern),
+            behavior.into(),
+            invert.unwrap_or(false),
+        )?;
+
+        Ok(Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Split(split))),
+        })
+    }
+
+    pub fn get_pattern(&self) -> RustPattern {
+        self.with_subtype(|split| serialized_field::<_, SplitPattern>(split, "pattern").into())
+    }
+
+    pub fn get_behavior(&self) -> RustSplitDelimiterBehavior {
+        self.with_subtype(|split| {
+            serialized_field::<_, SplitDelimiterBehavior>(split, "behavior").into()
+        })
+    }
+
+    pub fn get_invert(&self) -> bool {
+        self.with_subtype(|split| serialized_field(split, "invert"))
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this Split, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// This pre-tokenizer simply splits on punctuation as individual characters.
+pub struct RustPunctuation {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl RustPunctuation {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Punctuation) -> R,
+    {
+        if let PreTokenizerWrapper::Punctuation(inner) = &*self.pre_tokenizer.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the pre-tokenizer must be a `Punctuation`")
+        }
+    }
+}
+
+impl RustPunctuation {
+    pub fn new(behavior: Option<RustSplitDelimiterBehavior>) -> Self {
+        let punctuation = behavior
+            .map(|behavior| Punctuation::new(behavior.into()))
+            .unwrap_or_default();
+
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Punctuation(punctuation))),
+        }
+    }
+
+    pub fn get_behavior(&self) -> RustSplitDelimiterBehavior {
+        self.with_subtype(|punctuation| {
+            serialized_field::<_, SplitDelimiterBehavior>(punctuation, "behavior").into()
+        })
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this Punctuation, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// This pre-tokenizer simply splits using the digits in separate tokens
+///
+/// If `individual_digits` is set to true, each digit is split into its own token:
+/// "Call 123 please" becomes "Call ", "1", "2", "3", " please".
+/// Otherwise digits are kept together: "Call ", "123", " please".
+pub struct RustDigits {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl RustDigits {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Digits) -> R,
+    {
+        if let PreTokenizerWrapper::Digits(inner) = &*self.pre_tokenizer.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the pre-tokenizer must be a `Digits`")
+        }
+    }
+}
+
+impl RustDigits {
+    pub fn new(individual_digits: Option<bool>) -> Self {
+        let digits = Digits::new(individual_digits.unwrap_or(false));
+
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Digits(digits))),
+        }
+    }
+
+    pub fn get_individual_digits(&self) -> bool {
+        self.with_subtype(|digits| digits.individual_digits)
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this Digits, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// This pre-tokenizer simply splits on the provided char. Works like `.split(delimiter)`
+pub struct RustCharDelimiterSplit {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl RustCharDelimiterSplit {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&CharDelimiterSplit) -> R,
+    {
+        if let PreTokenizerWrapper::Delimiter(inner) = &*self.pre_tokenizer.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the pre-tokenizer must be a `Delimiter`")
+        }
+    }
+}
+
+impl RustCharDelimiterSplit {
+    pub fn new(delimiter: String) -> Result<Self> {
+        let delimiter = CharDelimiterSplit::new(single_char(&delimiter, "delimiter")?);
+
+        Ok(Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Delimiter(delimiter))),
+        })
+    }
+
+    pub fn get_delimiter(&self) -> String {
+        self.with_subtype(|delimiter| delimiter.delimiter.to_string())
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this CharDelimiterSplit, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// This pre-tokenizer simply splits on the whitespace. Works like `.split()`
+pub struct RustWhitespaceSplit {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl Default for RustWhitespaceSplit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustWhitespaceSplit {
+    pub fn new() -> Self {
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::WhitespaceSplit(
+                WhitespaceSplit,
+            ))),
+        }
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this WhitespaceSplit, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// BertPreTokenizer
+///
+/// This pre-tokenizer splits tokens on spaces, and also on punctuation.
+/// Each occurence of a punctuation character will be treated separately.
+pub struct RustBertPreTokenizer {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl Default for RustBertPreTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustBertPreTokenizer {
+    pub fn new() -> Self {
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::BertPreTokenizer(
+                BertPreTokenizer,
+            ))),
+        }
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this BertPreTokenizer, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// This pre-tokenizer splits on characters that belong to different language family
+///
+/// It roughly follows https://github.com/google/sentencepiece/blob/master/data/Scripts.txt
+/// Actually Hiragana and Katakana are fused with Han, and 0x30FC is Han too.
+/// This mimicks SentencePiece Unigram implementation.
+pub struct RustUnicodeScripts {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl Default for RustUnicodeScripts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustUnicodeScripts {
+    pub fn new() -> Self {
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::UnicodeScripts(
+                UnicodeScripts::new(),
+            ))),
+        }
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this UnicodeScripts, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+/// This pre-tokenizer composes other pre-tokenizers and applies them in sequence
+pub struct RustPreTokenizerSequence {
+    pre_tokenizer: Arc<RwLock<PreTokenizerWrapper>>,
+}
+
+impl RustPreTokenizerSequence {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Sequence) -> R,
+    {
+        if let PreTokenizerWrapper::Sequence(inner) = &*self.pre_tokenizer.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the pre-tokenizer must be a `Sequence`")
+        }
+    }
+}
+
+impl RustPreTokenizerSequence {
+    pub fn new(pre_tokenizers: Vec<Arc<RustPreTokenizer>>) -> Self {
+        let pre_tokenizers = pre_tokenizers
+            .iter()
+            .map(|p| p.pre_tokenizer.read().unwrap().clone())
+            .collect();
+
+        Self {
+            pre_tokenizer: Arc::new(RwLock::new(PreTokenizerWrapper::Sequence(Sequence::new(
+                pre_tokenizers,
+            )))),
+        }
+    }
+
+    /// Returns the pre-tokenizers composing this sequence.
+    ///
+    /// Like the ones given to `new`, they are copies: modifying them doesn't affect this sequence.
+    pub fn get_pre_tokenizers(&self) -> Vec<Arc<RustPreTokenizer>> {
+        self.with_subtype(|sequence| {
+            serialized_field::<_, Vec<PreTokenizerWrapper>>(sequence, "pretokenizers")
+                .into_iter()
+                .map(|p| Arc::new(p.into()))
+                .collect()
+        })
+    }
+
+    pub fn pre_tokenize_str(&self, s: &str) -> Result<Vec<RustPreTokenizedSplit>> {
+        self.as_pre_tokenizer().pre_tokenize_str(s)
+    }
+
+    /// Returns a pre-tokenizer handle sharing this Sequence, to be given to a `RustTokenizer`.
+    pub fn as_pre_tokenizer(&self) -> Arc<RustPreTokenizer> {
+        Arc::new(RustPreTokenizer {
+            pre_tokenizer: self.pre_tokenizer.clone(),
+        })
+    }
+}
+
+// Associated functions
+
+/// Returns the alphabet used by the ByteLevel pre-tokenizer.
+///
+/// Since the ByteLevel works as its name suggests, at the byte level, it encodes each byte
+/// value to a unique visible character. This means that there is a total of 256 different
+/// characters composing this alphabet. It is meant to seed the `initial_alphabet` of a trainer.
+pub fn byte_level_alphabet() -> Vec<String> {
+    let mut alphabet: Vec<String> = ByteLevel::alphabet()
+        .into_iter()
+        .map(|c| c.to_string())
+        .collect();
+
+    alphabet.sort();
+    alphabet
 }
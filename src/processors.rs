@@ -0,0 +1,632 @@
+use crate::error::{Result, TokenizersError};
+use crate::utils::serialized_field;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tk::processors::bert::BertProcessing;
+use tk::processors::byte_level::ByteLevel;
+use tk::processors::roberta::RobertaProcessing;
+use tk::processors::sequence::Sequence;
+use tk::processors::template::{SpecialToken, TemplateProcessing};
+use tk::processors::PostProcessorWrapper;
+use tk::{Encoding, PostProcessor};
+use tokenizers as tk;
+
+/// Base class for all post-processors
+///
+/// This class is not supposed to be instantiated directly. Instead, any implementation of
+/// a PostProcessor will return an instance of this class when converted with
+/// `as_post_processor`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustPostProcessor {
+    #[serde(flatten)]
+    pub(crate) processor: Arc<RwLock<PostProcessorWrapper>>,
+}
+
+impl From<PostProcessorWrapper> for RustPostProcessor {
+    fn from(processor: PostProcessorWrapper) -> Self {
+        Self {
+            processor: Arc::new(RwLock::new(processor)),
+        }
+    }
+}
+
+impl PostProcessor for RustPostProcessor {
+    fn added_tokens(&self, is_pair: bool) -> usize {
+        self.processor.read().unwrap().added_tokens(is_pair)
+    }
+
+    fn process(
+        &self,
+        encoding: Encoding,
+        pair_encoding: Option<Encoding>,
+        add_special_tokens: bool,
+    ) -> tk::Result<Encoding> {
+        self.processor
+            .read()
+            .unwrap()
+            .process(encoding, pair_encoding, add_special_tokens)
+    }
+
+    fn process_encodings(
+        &self,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+    ) -> tk::Result<Vec<Encoding>> {
+        self.processor
+            .read()
+            .unwrap()
+            .process_encodings(encodings, add_special_tokens)
+    }
+}
+
+impl RustPostProcessor {
+    /// Return the number of special tokens that would be added for single/pair sentences.
+    pub fn num_special_tokens_to_add(&self, is_pair: bool) -> u32 {
+        self.added_tokens(is_pair) as u32
+    }
+
+    /// A post-processor which neither adds special tokens nor changes the offsets, like an
+    /// empty `Sequence`.
+    pub(crate) fn identity() -> Self {
+        PostProcessorWrapper::Sequence(Sequence::new(vec![])).into()
+    }
+
+    /// Returns the special tokens of this post-processor by identifier, when it is a
+    /// TemplateProcessing.
+    pub(crate) fn template_special_tokens(&self) -> HashMap<String, RustSpecialToken> {
+        match &*self.processor.read().unwrap() {
+            PostProcessorWrapper::Template(template) => {
+                serialized_field(template, "special_tokens")
+            }
+            _ => HashMap::new(),
+        }
+    }
+
+    /// Returns the concrete ByteLevel processor handle sharing this post-processor, if any.
+    pub fn as_byte_level(&self) -> Option<Arc<RustByteLevelProcessor>> {
+        match *self.processor.read().unwrap() {
+            PostProcessorWrapper::ByteLevel(_) => Some(Arc::new(RustByteLevelProcessor {
+                processor: self.processor.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete TemplateProcessing processor handle sharing this post-processor, if any.
+    pub fn as_template(&self) -> Option<Arc<RustTemplateProcessing>> {
+        match *self.processor.read().unwrap() {
+            PostProcessorWrapper::Template(_) => Some(Arc::new(RustTemplateProcessing {
+                processor: self.processor.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete BertProcessing processor handle sharing this post-processor, if any.
+    pub fn as_bert(&self) -> Option<Arc<RustBertProcessing>> {
+        match *self.processor.read().unwrap() {
+            PostProcessorWrapper::Bert(_) => Some(Arc::new(RustBertProcessing {
+                processor: self.processor.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete RobertaProcessing processor handle sharing this post-processor, if any.
+    pub fn as_roberta(&self) -> Option<Arc<RustRobertaProcessing>> {
+        match *self.processor.read().unwrap() {
+            PostProcessorWrapper::Roberta(_) => Some(Arc::new(RustRobertaProcessing {
+                processor: self.processor.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete Sequence processor handle sharing this post-processor, if any.
+    pub fn as_sequence(&self) -> Option<Arc<RustPostProcessorSequence>> {
+        match *self.processor.read().unwrap() {
+            PostProcessorWrapper::Sequence(_) => Some(Arc::new(RustPostProcessorSequence {
+                processor: self.processor.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// A special token used by a post-processor, along with its id.
+pub struct RustTokenId {
+    pub token: String,
+    pub id: u32,
+}
+
+impl From<RustTokenId> for (String, u32) {
+    fn from(token: RustTokenId) -> Self {
+        (token.token, token.id)
+    }
+}
+
+impl From<(String, u32)> for RustTokenId {
+    fn from((token, id): (String, u32)) -> Self {
+        Self { token, id }
+    }
+}
+
+/// A special token referenced by a template, possibly made of several tokens.
+///
+/// - Parameters:
+///     - id:
+///       The identifier used in the template, e.g. `[CLS]`
+///     - ids:
+///       The ids of the tokens inserted in place of the identifier
+///     - tokens:
+///       The tokens inserted in place of the identifier, one per id
+#[derive(Deserialize)]
+pub struct RustSpecialToken {
+    pub id: String,
+    pub ids: Vec<u32>,
+    pub tokens: Vec<String>,
+}
+
+/// This post-processor takes care of trimming the offsets.
+///
+/// By default, the ByteLevel BPE might include whitespaces in the produced tokens. If you
+/// don't want the offsets to include these whitespaces, then this PostProcessor must be used.
+pub struct RustByteLevelProcessor {
+    processor: Arc<RwLock<PostProcessorWrapper>>,
+}
+
+impl RustByteLevelProcessor {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&ByteLevel) -> R,
+    {
+        if let PostProcessorWrapper::ByteLevel(byte_level) = &*self.processor.read().unwrap() {
+            callback(byte_level)
+        } else {
+            unreachable!("the post-processor must be a `ByteLevel`")
+        }
+    }
+}
+
+impl RustByteLevelProcessor {
+    pub fn new(trim_offsets: Option<bool>) -> Self {
+        let mut byte_level = ByteLevel::default();
+
+        if let Some(trim_offsets) = trim_offsets {
+            byte_level = byte_level.trim_offsets(trim_offsets);
+        }
+
+        Self {
+            processor: Arc::new(RwLock::new(byte_level.into())),
+        }
+    }
+
+    pub fn get_trim_offsets(&self) -> bool {
+        self.with_subtype(|byte_level| byte_level.trim_offsets)
+    }
+
+    /// Returns a post-processor handle sharing this ByteLevel processor, to be given to a
+    /// `RustTokenizer`.
+    pub fn as_post_processor(&self) -> Arc<RustPostProcessor> {
+        Arc::new(RustPostProcessor {
+            processor: self.processor.clone(),
+        })
+    }
+}
+
+/// Provides a way to specify templates in order to add the special tokens to each
+/// input sequence as relevant.
+///
+/// Let's take `BERT` tokenizer as an example. It uses two special tokens, used to
+/// delimitate each sequence. `[CLS]` is always used at the beginning of the first
+/// sequence, and `[SEP]` is added at the end of both the first, and the pair
+/// sequences. The final result looks like this:
+///
+/// - Single sequence: `[CLS] Hello there [SEP]`
+/// - Pair sequences: `[CLS] My name is Anthony [SEP] What is my name? [SEP]`
+///
+/// With the type ids as following:
+///
+/// ```markdown
+/// [CLS]   ...   [SEP]   ...   [SEP]
+///   0      0      0      1      1
+/// ```
+///
+/// You can achieve such behavior using a TemplateProcessing:
+///
+/// ```text
+/// TemplateProcessing(
+///     single: "[CLS] $0 [SEP]",
+///     pair: "[CLS] $A [SEP] $B:1 [SEP]:1",
+///     special_tokens: [("[CLS]", 1), ("[SEP]", 0)],
+/// )
+/// ```
+///
+/// In this example, each input sequence is identified using a `$` construct. This identifier
+/// lets us specify each input sequence, and the type_id to use. When nothing is specified,
+/// it uses the default values. Here are the different ways to specify it:
+///
+/// - Specifying the sequence, with default `type_id == 0`: `$A` or `$B`
+/// - Specifying the `type_id` with default `sequence == A`: `$0`, `$1`, `$2`, ...
+/// - Specifying both: `$A:0`, `$B:1`, ...
+///
+/// The same construct is used for special tokens: `<identifier>(:<type_id>)?`.
+pub struct RustTemplateProcessing {
+    processor: Arc<RwLock<PostProcessorWrapper>>,
+}
+
+impl RustTemplateProcessing {
+    pub fn new(
+        single: Option<String>,
+        pair: Option<String>,
+        special_tokens: Option<Vec<RustSpecialToken>>,
+    ) -> Result<Self> {
+        let mut builder = TemplateProcessing::builder();
+
+        if let Some(single) = single {
+            builder
+                .try_single(single)
+                .map_err(TokenizersError::ValueError)?;
+        }
+        if let Some(pair) = pair {
+            builder
+                .try_pair(pair)
+                .map_err(TokenizersError::ValueError)?;
+        }
+        if let Some(special_tokens) = special_tokens {
+            let special_tokens = special_tokens
+                .into_iter()
+                .map(|t| SpecialToken::new(t.id, t.ids, t.tokens))
+                .collect::<tk::Result<Vec<_>>>()?;
+
+            builder.special_tokens(special_tokens);
+        }
+
+        let template = builder
+            .build()
+            .map_err(|e| TokenizersError::ValueError(e.to_string()))?;
+
+        Ok(Self {
+            processor: Arc::new(RwLock::new(template.into())),
+        })
+    }
+
+    /// Returns a post-processor handle sharing this TemplateProcessing processor, to be given to a
+    /// `RustTokenizer`.
+    pub fn as_post_processor(&self) -> Arc<RustPostProcessor> {
+        Arc::new(RustPostProcessor {
+            processor: self.processor.clone(),
+        })
+    }
+}
+
+/// This post-processor takes care of adding the special tokens needed by
+/// a Bert model:
+///
+/// - a SEP token
+/// - a CLS token
+pub struct RustBertProcessing {
+    processor: Arc<RwLock<PostProcessorWrapper>>,
+}
+
+impl RustBertProcessing {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&BertProcessing) -> R,
+    {
+        if let PostProcessorWrapper::Bert(inner) = &*self.processor.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the post-processor must be a `Bert`")
+        }
+    }
+}
+
+impl RustBertProcessing {
+    pub fn new(sep: RustTokenId, cls: RustTokenId) -> Self {
+        let bert = BertProcessing::new(sep.into(), cls.into());
+
+        Self {
+            processor: Arc::new(RwLock::new(bert.into())),
+        }
+    }
+
+    pub fn get_sep(&self) -> RustTokenId {
+        self.with_subtype(|bert| serialized_field::<_, (String, u32)>(bert, "sep").into())
+    }
+
+    pub fn get_cls(&self) -> RustTokenId {
+        self.with_subtype(|bert| serialized_field::<_, (String, u32)>(bert, "cls").into())
+    }
+
+    /// Returns a post-processor handle sharing this BertProcessing processor, to be given to a
+    /// `RustTokenizer`.
+    pub fn as_post_processor(&self) -> Arc<RustPostProcessor> {
+        Arc::new(RustPostProcessor {
+            processor: self.processor.clone(),
+        })
+    }
+}
+
+/// This post-processor takes care of adding the special tokens needed by
+/// a Roberta model:
+///
+/// - a SEP token
+/// - a CLS token
+///
+/// It also takes care of trimming the offsets.
+/// By default, the ByteLevel BPE might include whitespaces in the produced tokens. If you
+/// don't want the offsets to include these whitespaces, then this PostProcessor should be
+/// initialized with `trim_offsets=true`.
+pub struct RustRobertaProcessing {
+    processor: Arc<RwLock<PostProcessorWrapper>>,
+}
+
+impl RustRobertaProcessing {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&RobertaProcessing) -> R,
+    {
+        if let PostProcessorWrapper::Roberta(inner) = &*self.processor.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the post-processor must be a `Roberta`")
+        }
+    }
+}
+
+impl RustRobertaProcessing {
+    pub fn new(
+        sep: RustTokenId,
+        cls: RustTokenId,
+        trim_offsets: Option<bool>,
+        add_prefix_space: Option<bool>,
+    ) -> Self {
+        let mut roberta = RobertaProcessing::new(sep.into(), cls.into());
+
+        if let Some(trim_offsets) = trim_offsets {
+            roberta = roberta.trim_offsets(trim_offsets);
+        }
+        if let Some(add_prefix_space) = add_prefix_space {
+            roberta = roberta.add_prefix_space(add_prefix_space);
+        }
+
+        Self {
+            processor: Arc::new(RwLock::new(roberta.into())),
+        }
+    }
+
+    pub fn get_sep(&self) -> RustTokenId {
+        self.with_subtype(|roberta| serialized_field::<_, (String, u32)>(roberta, "sep").into())
+    }
+
+    pub fn get_cls(&self) -> RustTokenId {
+        self.with_subtype(|roberta| serialized_field::<_, (String, u32)>(roberta, "cls").into())
+    }
+
+    pub fn get_trim_offsets(&self) -> bool {
+        self.with_subtype(|roberta| serialized_field(roberta, "trim_offsets"))
+    }
+
+    pub fn get_add_prefix_space(&self) -> bool {
+        self.with_subtype(|roberta| serialized_field(roberta, "add_prefix_space"))
+    }
+
+    /// Returns a post-processor handle sharing this RobertaProcessing processor, to be given to a
+    /// `RustTokenizer`.
+    pub fn as_post_processor(&self) -> Arc<RustPostProcessor> {
+        Arc::new(RustPostProcessor {
+            processor: self.processor.clone(),
+        })
+    }
+}
+
+/// Allows concatenating multiple other PostProcessor as a Sequence.
+/// All the post-processors run in sequence in the given order.
+pub struct RustPostProcessorSequence {
+    processor: Arc<RwLock<PostProcessorWrapper>>,
+}
+
+impl RustPostProcessorSequence {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Sequence) -> R,
+    {
+        if let PostProcessorWrapper::Sequence(inner) = &*self.processor.read().unwrap() {
+            callback(inner)
+        } else {
+            unreachable!("the post-processor must be a `Sequence`")
+        }
+    }
+}
+
+impl RustPostProcessorSequence {
+    pub fn new(processors: Vec<Arc<RustPostProcessor>>) -> Self {
+        let processors = processors
+            .iter()
+            .map(|p| p.processor.read().unwrap().clone())
+            .collect();
+
+        Self {
+            processor: Arc::new(RwLock::new(Sequence::new(processors).into())),
+        }
+    }
+
+    /// Returns the post-processors composing this sequence.
+    ///
+    /// Like the ones given to `new`, they are copies: modifying them doesn't affect this sequence.
+    pub fn get_processors(&self) -> Vec<Arc<RustPostProcessor>> {
+        self.with_subtype(|sequence| {
+            serialized_field::<_, Vec<PostProcessorWrapper>>(sequence, "processors")
+                .into_iter()
+                .map(|p| Arc::new(p.into()))
+                .collect()
+        })
+    }
+
+    /// Returns a post-processor handle sharing this Sequence processor, to be given to a
+    /// `RustTokenizer`.
+    pub fn as_post_processor(&self) -> Arc<RustPostProcessor> {
+        Arc::new(RustPostProcessor {
+            processor: self.processor.clone(),
+        })
+    }
+}
+
+/// A piece of a multi-segment template.
+///
+/// Multi-segment templates extend the `TemplateProcessing` syntax to any number of segments:
+/// each segment is referenced with a letter (`$A`, `$B`, `$C`, ...), while `$0`, `$1`, ... still
+/// reference the first segment with the given type id. Special tokens are the ones of the
+/// `TemplateProcessing` post-processor of the tokenizer.
+pub(crate) enum SegmentPiece {
+    Segment { index: usize, type_id: u32 },
+    SpecialToken { token: String, type_id: u32 },
+}
+
+impl SegmentPiece {
+    /// The default template, concatenating the segments with their index as type id.
+    pub(crate) fn concatenation(n_segments: usize) -> Vec<Self> {
+        (0..n_segments)
+            .map(|index| Self::Segment {
+                index,
+                type_id: index as u32,
+            })
+            .collect()
+    }
+
+    /// Parse a whitespace separated template, e.g. `[CLS] $A [SEP] $B:1 [SEP]:1 $C:2`
+    pub(crate) fn parse_template(template: &str) -> Result<Vec<Self>> {
+        template
+            .split_whitespace()
+            .map(|piece| {
+                let err = || {
+                    TokenizersError::ValueError(format!(
+                        "Cannot build a template piece from \"{}\"",
+                        piece
+                    ))
+                };
+
+                let (id, type_id) = match piece.split_once(':') {
+                    Some((id, type_id)) => (id, Some(type_id.parse().map_err(|_| err())?)),
+                    None => (piece, None),
+                };
+
+                let piece = match id.strip_prefix('$') {
+                    None => Self::SpecialToken {
+                        token: id.to_owned(),
+                        type_id: type_id.unwrap_or(0),
+                    },
+                    Some("") => Self::Segment {
+                        index: 0,
+                        type_id: type_id.unwrap_or(0),
+                    },
+                    Some(rest) => match rest.parse::<u32>() {
+                        Ok(n) if type_id.is_none() => Self::Segment {
+                            index: 0,
+                            type_id: n,
+                        },
+                        Ok(_) => return Err(err()),
+                        Err(_) => {
+                            let mut chars = rest.chars();
+
+                            match (chars.next(), chars.next()) {
+                                (Some(c), None) if c.is_ascii_alphabetic() => Self::Segment {
+                                    index: (c.to_ascii_uppercase() as u8 - b'A') as usize,
+                                    type_id: type_id.unwrap_or(0),
+                                },
+                                _ => return Err(err()),
+                            }
+                        }
+                    },
+                };
+
+                Ok(piece)
+            })
+            .collect()
+    }
+}
+
+/// Copies the encoding of a sequence with the given type id, and without its sequence ranges.
+///
+/// `set_sequence_id` adds to the sequence ranges of an encoding, so the ones upstream set when
+/// encoding the sequence must be dropped before giving it another id.
+pub(crate) fn with_type_id(encoding: &Encoding, type_id: u32) -> Encoding {
+    Encoding::new(
+        encoding.get_ids().to_vec(),
+        vec![type_id; encoding.len()],
+        encoding.get_tokens().to_vec(),
+        encoding.get_word_ids().to_vec(),
+        encoding.get_offsets().to_vec(),
+        encoding.get_special_tokens_mask().to_vec(),
+        encoding.get_attention_mask().to_vec(),
+        encoding.get_overflowing().clone(),
+        HashMap::new(),
+    )
+}
+
+/// Applies the given template to the encodings of each segment, and merges the result.
+///
+/// The special tokens of the template are looked up in `special_tokens`. They are inserted
+/// only when `add_special_tokens` is set, but they must exist in any case.
+pub(crate) fn apply_segment_template(
+    template: &[SegmentPiece],
+    encodings: &[Encoding],
+    add_special_tokens: bool,
+    special_tokens: &HashMap<String, RustSpecialToken>,
+) -> Result<Encoding> {
+    let mut used = vec![false; encodings.len()];
+    let mut pieces = Vec::with_capacity(template.len());
+
+    for piece in template {
+        match piece {
+            SegmentPiece::Segment { index, type_id } => {
+                let encoding = encodings.get(*index).ok_or_else(|| {
+                    TokenizersError::ValueError(format!(
+                        "The template references the segment #{} but only {} segments were given",
+                        index,
+                        encodings.len()
+                    ))
+                })?;
+
+                let mut encoding = with_type_id(encoding, *type_id);
+                encoding.set_sequence_id(*index);
+                used[*index] = true;
+                pieces.push(encoding);
+            }
+            SegmentPiece::SpecialToken { token, type_id } => {
+                let special_token = special_tokens.get(token).ok_or_else(|| {
+                    TokenizersError::ValueError(format!(
+                        "Missing special token `{}` in the `TemplateProcessing` post-processor",
+                        token
+                    ))
+                })?;
+
+                if add_special_tokens {
+                    let len = special_token.ids.len();
+                    pieces.push(Encoding::new(
+                        special_token.ids.clone(),
+                        vec![*type_id; len],
+                        special_token.tokens.clone(),
+                        vec![None; len],
+                        vec![(0, 0); len],
+                        vec![1; len],
+                        vec![1; len],
+                        vec![],
+                        HashMap::new(),
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(index) = used.iter().position(|used| !used) {
+        return Err(TokenizersError::ValueError(format!(
+            "The template must use every segment but the segment #{} is missing",
+            index
+        )));
+    }
+
+    Ok(Encoding::merge(pieces, false))
+}
@@ -1,15 +1,20 @@
-use super::error::Result;
-use crate::utils::RustVocab;
-use crate::{RustBpe, RustBpeTrainer, RustWhitespace};
-use std::sync::{Arc, RwLock};
+use super::error::{Result, TokenizersError};
+use crate::processors::{apply_segment_template, with_type_id, SegmentPiece};
+use crate::utils::{RustOffsets, RustPaddingParams, RustTruncationParams, RustVocab};
+use crate::{
+    RustDecoder, RustModel, RustNormalizer, RustPostProcessor, RustPreTokenizer, RustTrainer,
+};
+use std::borrow::Borrow;
+use std::sync::{Arc, Mutex, RwLock};
+use tk::parallelism::MaybeParallelIterator;
 use tk::{
-    AddedToken, DecoderWrapper, EncodeInput, InputSequence, Model, NormalizerWrapper,
-    PostProcessorWrapper, TokenizerImpl,
+    AddedToken, EncodeInput, Encoding, InputSequence, Model, PostProcessor, TokenizerImpl,
+    TruncationParams, TruncationStrategy,
 };
 use tokenizers as tk;
 
 type Tokenizer =
-    TokenizerImpl<RustBpe, NormalizerWrapper, RustWhitespace, PostProcessorWrapper, DecoderWrapper>;
+    TokenizerImpl<RustModel, RustNormalizer, RustPreTokenizer, RustPostProcessor, RustDecoder>;
 
 pub enum RustInputSequence {
     Raw { raw_value: String },
@@ -25,12 +30,246 @@ impl From<RustInputSequence> for InputSequence<'_> {
     }
 }
 
+/// A single sequence or a pair of sequences to encode.
+pub enum RustEncodeInput {
+    Single {
+        input: RustInputSequence,
+    },
+    Dual {
+        input: RustInputSequence,
+        pair: RustInputSequence,
+    },
+}
+
+impl From<RustEncodeInput> for EncodeInput<'_> {
+    fn from(input: RustEncodeInput) -> Self {
+        match input {
+            RustEncodeInput::Single { input } => EncodeInput::Single(input.into()),
+            RustEncodeInput::Dual { input, pair } => EncodeInput::Dual(input.into(), pair.into()),
+        }
+    }
+}
+
+/// The text a single id adds to a decoded string.
+pub struct RustDecodedPiece {
+    pub id: u32,
+    pub text: String,
+    /// The char span of `text` within the decoded string.
+    pub offsets: RustOffsets,
+}
+
+/// Decodes ids one at a time, producing the text each of them adds to the output.
+///
+/// An id can't be decoded on its own: decoders may depend on the previous tokens (e.g.
+/// Metaspace strips the leading space of the first token only), and byte-level tokens may
+/// hold a part of a character. So we decode the ids of the previous fragment as context,
+/// followed by the pending ids, and emit the new text once it no longer ends with an
+/// incomplete character.
+pub(crate) struct DecodeStream {
+    skip_special_tokens: bool,
+    /// The context ids, followed by the pending ids.
+    ids: Vec<u32>,
+    /// The number of context ids at the start of `ids`.
+    prefix_len: usize,
+    /// The decoded context ids.
+    prefix: String,
+}
+
+impl DecodeStream {
+    pub(crate) fn new(skip_special_tokens: bool) -> Self {
+        Self {
+            skip_special_tokens,
+            ids: vec![],
+            prefix_len: 0,
+            prefix: String::new(),
+        }
+    }
+
+    /// Returns the text added by `id`, or `None` while it is still pending.
+    pub(crate) fn step(&mut self, tokenizer: &Tokenizer, id: u32) -> Result<Option<String>> {
+        self.ids.push(id);
+
+        let text = tokenizer.decode(&self.ids, self.skip_special_tokens)?;
+        if text.len() <= self.prefix.len() || text.ends_with(char::REPLACEMENT_CHARACTER) {
+            return Ok(None);
+        }
+
+        self.emit(tokenizer, text).map(Some)
+    }
+
+    /// Returns the text of the pending ids, even if it ends with an incomplete character.
+    pub(crate) fn flush(&mut self, tokenizer: &Tokenizer) -> Result<Option<String>> {
+        if self.ids.len() == self.prefix_len {
+            return Ok(None);
+        }
+
+        let text = tokenizer.decode(&self.ids, self.skip_special_tokens)?;
+        if text.len() <= self.prefix.len() {
+            return Ok(None);
+        }
+
+        self.emit(tokenizer, text).map(Some)
+    }
+
+    fn emit(&mut self, tokenizer: &Tokenizer, text: String) -> Result<String> {
+        let fragment = text
+            .strip_prefix(self.prefix.as_str())
+            .ok_or_else(|| {
+                TokenizersError::Exception(format!(
+                    "Decoded text {:?} doesn't start with the previous text {:?}",
+                    text, self.prefix
+                ))
+            })?
+            .to_owned();
+
+        self.ids.drain(..self.prefix_len);
+        self.prefix_len = self.ids.len();
+        self.prefix = tokenizer.decode(&self.ids, self.skip_special_tokens)?;
+
+        Ok(fragment)
+    }
+}
+
+/// The unit of the offsets of an encoding.
+///
+///  - Byte => UTF-8 bytes
+///  - Char => Unicode scalar values
+///  - Utf16 => UTF-16 code units, like `NSString` and `NSRange`
+pub enum RustOffsetType {
+    Byte,
+    Char,
+    Utf16,
+}
+
+/// The UTF-16 position of each byte of the given string. The bytes of a character all get
+/// the position of its first code unit.
+fn utf16_positions(s: &str) -> Vec<usize> {
+    let mut positions = vec![0; s.len() + 1];
+    let mut position = 0;
+
+    for (i, c) in s.char_indices() {
+        positions[i..i + c.len_utf8()].fill(position);
+        position += c.len_utf16();
+    }
+    positions[s.len()] = position;
+
+    positions
+}
+
+/// Converts the byte offsets of the given encoding, and of its overflowing ones, to UTF-16
+/// code units. `sequences` holds the words of each input sequence, a raw sequence being a
+/// single word: the offsets of a pre-tokenized sequence are relative to each of its words.
+fn convert_offsets_to_utf16(encoding: &mut Encoding, sequences: &[Vec<Vec<usize>>]) {
+    let sequence_ids = encoding.get_sequence_ids();
+    let word_ids = encoding.get_word_ids().to_vec();
+
+    for (i, offsets) in encoding.get_offsets_mut().iter_mut().enumerate() {
+        let words = match sequence_ids[i].and_then(|sequence| sequences.get(sequence)) {
+            Some(words) => words,
+            None => continue,
+        };
+        let word = if words.len() == 1 {
+            words.first()
+        } else {
+            word_ids[i].and_then(|word| words.get(word as usize))
+        };
+
+        if let Some(positions) = word {
+            let last = positions.len() - 1;
+            offsets.0 = positions[offsets.0.min(last)];
+            offsets.1 = positions[offsets.1.min(last)];
+        }
+    }
+
+    for overflowing in encoding.get_overflowing_mut() {
+        convert_offsets_to_utf16(overflowing, sequences);
+    }
+}
+
+/// The type of the elements of a tensor.
+#[derive(Clone, Copy)]
+pub enum RustBufferDType {
+    Int32,
+    Int64,
+}
+
+/// Tensors of the same shape, stacked in a single row-major buffer. Swift reads the elements
+/// in place, through their address, so they must not move until the buffer is dropped.
+pub struct RustBatchBuffer {
+    /// The name of each tensor, along the first dimension.
+    names: Vec<String>,
+    /// `[names.len(), batch size, sequence length]`
+    shape: Vec<usize>,
+    dtype: RustBufferDType,
+    /// The elements, in native byte order.
+    data: Vec<u8>,
+}
+
+impl RustBatchBuffer {
+    pub fn get_names(&self) -> Vec<String> {
+        self.names.clone()
+    }
+
+    pub fn get_shape(&self) -> Vec<usize> {
+        self.shape.clone()
+    }
+
+    pub fn get_dtype(&self) -> RustBufferDType {
+        self.dtype
+    }
+
+    /// The address of the elements, valid as long as the buffer is alive.
+    pub fn get_data_address(&self) -> u64 {
+        self.data.as_ptr() as u64
+    }
+
+    /// The size of the elements, in bytes.
+    pub fn get_data_length(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// Appends the given values to the buffer, in native byte order.
+fn write_elements<I>(data: &mut Vec<u8>, dtype: &RustBufferDType, values: I) -> Result<()>
+where
+    I: IntoIterator,
+    I::Item: Borrow<u32>,
+{
+    for value in values {
+        let value = *value.borrow();
+
+        match dtype {
+            RustBufferDType::Int32 => {
+                let value = i32::try_from(value).map_err(|_| {
+                    TokenizersError::ValueError(format!("{} doesn't fit in an int32 buffer", value))
+                })?;
+                data.extend_from_slice(&value.to_ne_bytes());
+            }
+            RustBufferDType::Int64 => data.extend_from_slice(&i64::from(value).to_ne_bytes()),
+        }
+    }
+
+    Ok(())
+}
+
+/// The position of each token among the attended ones. The padding tokens get 0.
+fn position_ids(attention_mask: &[u32]) -> impl Iterator<Item = u32> + '_ {
+    attention_mask.iter().scan(0, |position, &mask| {
+        if mask == 0 {
+            return Some(0);
+        }
+
+        *position += 1;
+        Some(*position - 1)
+    })
+}
+
 pub struct RustTokenizer {
     tokenizer: Arc<RwLock<Tokenizer>>,
 }
 
 impl RustTokenizer {
-    pub fn new(model: Arc<RustBpe>) -> Self {
+    pub fn new(model: Arc<RustModel>) -> Self {
         let tokenizer = Tokenizer::new(model.as_ref().clone());
 
         Self {
@@ -67,12 +306,31 @@ impl RustTokenizer {
         })
     }
 
+    /// Encode the given sequence, and its pair if any. The offsets are in chars unless
+    /// `offset_type` says otherwise.
     pub fn encode(
         &self,
         input: RustInputSequence,
         pair: Option<RustInputSequence>,
         add_special_tokens: bool,
+        offset_type: Option<RustOffsetType>,
     ) -> Result<Arc<RustEncoding>> {
+        let offset_type = offset_type.unwrap_or(RustOffsetType::Char);
+
+        // The UTF-16 offsets are computed from the byte ones, with the words of each sequence
+        let sequences: Vec<Vec<Vec<usize>>> = match offset_type {
+            RustOffsetType::Utf16 => std::iter::once(&input)
+                .chain(pair.as_ref())
+                .map(|sequence| match sequence {
+                    RustInputSequence::Raw { raw_value } => vec![utf16_positions(raw_value)],
+                    RustInputSequence::PreTokenized { tokens } => {
+                        tokens.iter().map(|token| utf16_positions(token)).collect()
+                    }
+                })
+                .collect(),
+            _ => vec![],
+        };
+
         let input: InputSequence = match input {
             RustInputSequence::Raw { raw_value } => raw_value.into(),
             RustInputSequence::PreTokenized { tokens } => tokens.into(),
@@ -84,11 +342,161 @@ impl RustTokenizer {
             EncodeInput::Single(input)
         };
 
-        let encoding = self
-            .tokenizer
-            .read()
-            .unwrap()
-            .encode_char_offsets(input, add_special_tokens)?;
+        let tokenizer = self.tokenizer.read().unwrap();
+        let char_offsets = matches!(offset_type, RustOffsetType::Char);
+        let mut encoding = encode_input(
+            &tokenizer,
+            &unprocessed(&tokenizer),
+            input,
+            add_special_tokens,
+            char_offsets,
+        )?;
+
+        if let RustOffsetType::Utf16 = offset_type {
+            convert_offsets_to_utf16(&mut encoding, &sequences);
+        }
+
+        Ok(Arc::new(RustEncoding::new(Arc::new(encoding))))
+    }
+
+    /// Encode the given batch of inputs in parallel. Each input can be either a single
+    /// sequence or a pair of sequences.
+    pub fn encode_batch(
+        &self,
+        inputs: Vec<RustEncodeInput>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Arc<RustEncoding>>> {
+        let tokenizer = self.tokenizer.read().unwrap();
+        let encodings = encode_inputs(&tokenizer, inputs, add_special_tokens, true)?;
+
+        Ok(encodings
+            .into_iter()
+            .map(|encoding| Arc::new(RustEncoding::new(Arc::new(encoding))))
+            .collect())
+    }
+
+    /// Encode the given batch of inputs, and write the `input_ids`, `attention_mask`,
+    /// `token_type_ids` and optionally `position_ids` tensors into a single buffer. It is
+    /// still copied across the FFI, but once for the whole batch rather than once per field
+    /// of each encoding.
+    ///
+    /// The encodings are padded with the padding parameters of the tokenizer, if any, or to
+    /// the longest one of the batch otherwise. The overflowing encodings are left out.
+    pub fn encode_batch_to_buffer(
+        &self,
+        inputs: Vec<RustEncodeInput>,
+        add_special_tokens: bool,
+        dtype: RustBufferDType,
+        with_position_ids: bool,
+    ) -> Result<Arc<RustBatchBuffer>> {
+        let tokenizer = self.tokenizer.read().unwrap();
+        let mut encodings = encode_inputs(&tokenizer, inputs, add_special_tokens, false)?;
+        let padding = tokenizer.get_padding().cloned().unwrap_or_default();
+        tk::pad_encodings(&mut encodings, &padding)?;
+
+        let batch_size = encodings.len();
+        let length = encodings.first().map_or(0, |encoding| encoding.len());
+        if encodings.iter().any(|encoding| encoding.len() != length) {
+            return Err(TokenizersError::ValueError(
+                "The encodings have different lengths, they must be truncated to the padding length"
+                    .into(),
+            ));
+        }
+
+        let mut names = vec!["input_ids", "attention_mask", "token_type_ids"];
+        if with_position_ids {
+            names.push("position_ids");
+        }
+
+        let element_size = match dtype {
+            RustBufferDType::Int32 => 4,
+            RustBufferDType::Int64 => 8,
+        };
+        let mut data = Vec::with_capacity(names.len() * batch_size * length * element_size);
+        for encoding in &encodings {
+            write_elements(&mut data, &dtype, encoding.get_ids())?;
+        }
+        for encoding in &encodings {
+            write_elements(&mut data, &dtype, encoding.get_attention_mask())?;
+        }
+        for encoding in &encodings {
+            write_elements(&mut data, &dtype, encoding.get_type_ids())?;
+        }
+        if with_position_ids {
+            for encoding in &encodings {
+                let attention_mask = encoding.get_attention_mask();
+                write_elements(&mut data, &dtype, position_ids(attention_mask))?;
+            }
+        }
+
+        Ok(Arc::new(RustBatchBuffer {
+            shape: vec![names.len(), batch_size, length],
+            names: names.into_iter().map(Into::into).collect(),
+            dtype,
+            data,
+        }))
+    }
+
+    /// Encode any number of segments at once, e.g. a system prompt, a context and a question.
+    ///
+    /// Each segment is encoded on its own, then the `template` lays them out along with the
+    /// special tokens of the `TemplateProcessing` post-processor. It uses the
+    /// `TemplateProcessing` syntax, with a letter per segment: `[CLS] $A [SEP] $B:1 [SEP]:1 $C:2`.
+    /// Without a template, the segments are concatenated and each of them gets its index as
+    /// type id.
+    pub fn encode_segments(
+        &self,
+        segments: Vec<RustInputSequence>,
+        template: Option<String>,
+        add_special_tokens: bool,
+    ) -> Result<Arc<RustEncoding>> {
+        if segments.is_empty() {
+            return Err(TokenizersError::ValueError(
+                "At least one segment must be given".into(),
+            ));
+        }
+
+        let template = match template {
+            Some(template) => SegmentPiece::parse_template(&template)?,
+            None => SegmentPiece::concatenation(segments.len()),
+        };
+
+        let tokenizer = self.tokenizer.read().unwrap();
+        let special_tokens = tokenizer
+            .get_post_processor()
+            .map(RustPostProcessor::template_special_tokens)
+            .unwrap_or_default();
+
+        let unprocessed = unprocessed(&tokenizer);
+        let mut encodings = segments
+            .into_iter()
+            .map(|segment| encode_sequence(&unprocessed, segment.into(), 0, true))
+            .collect::<tk::Result<Vec<_>>>()?;
+
+        // The truncation and the padding apply to the whole encoding, not to each segment
+        if let Some(truncation) = tokenizer.get_truncation() {
+            let n_special_tokens = if add_special_tokens {
+                template
+                    .iter()
+                    .filter_map(|piece| match piece {
+                        SegmentPiece::SpecialToken { token, .. } => special_tokens.get(token),
+                        SegmentPiece::Segment { .. } => None,
+                    })
+                    .map(|special_token| special_token.ids.len())
+                    .sum()
+            } else {
+                0
+            };
+
+            truncate_segments(&mut encodings, n_special_tokens, truncation)?;
+        }
+
+        let mut encoding =
+            apply_segment_template(&template, &encodings, add_special_tokens, &special_tokens)?;
+
+        if let Some(padding) = tokenizer.get_padding() {
+            tk::pad_encodings(std::slice::from_mut(&mut encoding), padding)?;
+        }
 
         Ok(Arc::new(RustEncoding::new(Arc::new(encoding))))
     }
@@ -98,10 +506,93 @@ impl RustTokenizer {
             .tokenizer
             .read()
             .unwrap()
-            .decode(ids, skip_special_tokens)?)
+            .decode(&ids, skip_special_tokens)?)
+    }
+
+    pub fn decode_batch(
+        &self,
+        sentences: Vec<Vec<u32>>,
+        skip_special_tokens: bool,
+    ) -> Result<Vec<String>> {
+        let sentences: Vec<&[u32]> = sentences.iter().map(|ids| ids.as_slice()).collect();
+
+        Ok(self
+            .tokenizer
+            .read()
+            .unwrap()
+            .decode_batch(&sentences, skip_special_tokens)?)
     }
 
-    pub fn train(&self, files: Vec<String>, trainer: Option<Arc<RustBpeTrainer>>) -> Result<()> {
+    /// Decodes the given ids like `decode`, but returns the text each id adds to the output.
+    ///
+    /// Joining the texts gives the decoded string. Ids which don't add any text on their own
+    /// (e.g. skipped special tokens, or the first bytes of a character) get an empty text at
+    /// the current position, and the text is given to the id completing it.
+    pub fn decode_pieces(
+        &self,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+    ) -> Result<Vec<RustDecodedPiece>> {
+        let tokenizer = self.tokenizer.read().unwrap();
+        let mut stream = DecodeStream::new(skip_special_tokens);
+        let mut pieces = Vec::with_capacity(ids.len());
+        let mut offset = 0;
+
+        for (i, &id) in ids.iter().enumerate() {
+            let mut text = stream.step(&tokenizer, id)?;
+            if text.is_none() && i == ids.len() - 1 {
+                text = stream.flush(&tokenizer)?;
+            }
+
+            let text = text.unwrap_or_default();
+            let start = offset;
+            offset += text.chars().count();
+
+            pieces.push(RustDecodedPiece {
+                id,
+                text,
+                offsets: (start, offset),
+            });
+        }
+
+        Ok(pieces)
+    }
+
+    /// Create a stream to decode ids one at a time, as they are generated.
+    pub fn decode_stream(&self, skip_special_tokens: bool) -> Arc<RustDecodeStream> {
+        Arc::new(RustDecodeStream {
+            tokenizer: self.tokenizer.clone(),
+            stream: Mutex::new(DecodeStream::new(skip_special_tokens)),
+        })
+    }
+
+    /// Create a matcher looking for the given stop strings in the decoded ids.
+    pub fn stop_sequence_matcher(
+        &self,
+        stops: Vec<String>,
+        skip_special_tokens: bool,
+    ) -> Result<Arc<RustStopSequenceMatcher>> {
+        if stops.is_empty() || stops.iter().any(|stop| stop.is_empty()) {
+            return Err(TokenizersError::ValueError(
+                "At least one stop string must be given, and none can be empty".into(),
+            ));
+        }
+
+        Ok(Arc::new(RustStopSequenceMatcher {
+            tokenizer: self.tokenizer.clone(),
+            state: Mutex::new(StopSequenceState {
+                stops,
+                stream: DecodeStream::new(skip_special_tokens),
+                text: String::new(),
+                fragments: vec![],
+                n_ids: 0,
+                first_pending: 0,
+                found: None,
+            }),
+        }))
+    }
+
+    pub fn train(&self, files: Vec<String>, trainer: Option<Arc<RustTrainer>>) -> Result<()> {
         let mut trainer = trainer.map_or_else(
             || self.tokenizer.read().unwrap().get_model().get_trainer(),
             |t| t.as_ref().clone(),
@@ -133,18 +624,87 @@ impl RustTokenizer {
         self.tokenizer.write().unwrap().add_special_tokens(&tokens)
     }
 
-    pub fn get_model(&self) -> Arc<RustBpe> {
+    /// Pad the encodings from now on, including the ones of `encode`. Without a fixed size,
+    /// a single encoding is only padded up to `pad_to_multiple_of`, if any.
+    pub fn with_padding(&self, params: RustPaddingParams) {
+        self.tokenizer
+            .write()
+            .unwrap()
+            .with_padding(Some(params.into()));
+    }
+
+    pub fn get_padding(&self) -> Option<RustPaddingParams> {
+        self.tokenizer
+            .read()
+            .unwrap()
+            .get_padding()
+            .map(|params| params.clone().into())
+    }
+
+    pub fn no_padding(&self) {
+        self.tokenizer.write().unwrap().with_padding(None);
+    }
+
+    /// Truncate the encodings from now on. The tokens which don't fit are kept in the
+    /// overflowing encodings, each of them starting with the last `stride` tokens of the
+    /// previous one.
+    pub fn with_truncation(&self, params: RustTruncationParams) -> Result<()> {
+        let mut tokenizer = self.tokenizer.write().unwrap();
+        let n_added_tokens = tokenizer
+            .get_post_processor()
+            .map_or(0, |processor| processor.added_tokens(false));
+
+        if params.max_length < n_added_tokens {
+            return Err(TokenizersError::ValueError(format!(
+                "`max_length` must be at least the number of added special tokens ({}) but was {}",
+                n_added_tokens, params.max_length
+            )));
+        }
+
+        tokenizer.with_truncation(Some(params.into()))?;
+        Ok(())
+    }
+
+    pub fn get_truncation(&self) -> Option<RustTruncationParams> {
+        self.tokenizer
+            .read()
+            .unwrap()
+            .get_truncation()
+            .map(|params| params.clone().into())
+    }
+
+    pub fn no_truncation(&self) {
+        // Only the truncation parameters can be rejected
+        let _ = self.tokenizer.write().unwrap().with_truncation(None);
+    }
+
+    pub fn get_model(&self) -> Arc<RustModel> {
         Arc::new(self.tokenizer.read().unwrap().get_model().clone())
     }
 
-    pub fn set_model(&self, model: Arc<RustBpe>) {
+    pub fn set_model(&self, model: Arc<RustModel>) {
         self.tokenizer
             .write()
             .unwrap()
             .with_model(model.as_ref().clone());
     }
 
-    pub fn get_pre_tokenizer(&self) -> Option<Arc<RustWhitespace>> {
+    pub fn get_normalizer(&self) -> Option<Arc<RustNormalizer>> {
+        self.tokenizer
+            .read()
+            .unwrap()
+            .get_normalizer()
+            .map(|n| Arc::new(n.clone()))
+    }
+
+    pub fn set_normalizer(&self, normalizer: Arc<RustNormalizer>) {
+        self.tokenizer
+            .write()
+            .unwrap()
+            .with_normalizer(normalizer.as_ref().clone());
+    }
+
+    pub fn get_pre_tokenizer(&self) -> Option<Arc<RustPreTokenizer>> {
         self.tokenizer
             .read()
             .unwrap()
@@ -152,12 +712,394 @@ impl RustTokenizer {
             .map(|pt| Arc::new(pt.clone()))
     }
 
-    pub fn set_pre_tokenizer(&self, pre_tokenizer: Arc<RustWhitespace>) {
+    pub fn set_pre_tokenizer(&self, pre_tokenizer: Arc<RustPreTokenizer>) {
         self.tokenizer
             .write()
             .unwrap()
             .with_pre_tokenizer(pre_tokenizer.as_ref().clone());
     }
+
+    /// Return the number of special tokens that would be added for single/pair sentences.
+    pub fn num_special_tokens_to_add(&self, is_pair: bool) -> u32 {
+        self.tokenizer
+            .read()
+            .unwrap()
+            .get_post_processor()
+            .map_or(0, |p| p.num_special_tokens_to_add(is_pair))
+    }
+
+    pub fn get_post_processor(&self) -> Option<Arc<RustPostProcessor>> {
+        self.tokenizer
+            .read()
+            .unwrap()
+            .get_post_processor()
+            .map(|pp| Arc::new(pp.clone()))
+    }
+
+    pub fn set_post_processor(&self, post_processor: Arc<RustPostProcessor>) {
+        self.tokenizer
+            .write()
+            .unwrap()
+            .with_post_processor(post_processor.as_ref().clone());
+    }
+
+    pub fn get_decoder(&self) -> Option<Arc<RustDecoder>> {
+        self.tokenizer
+            .read()
+            .unwrap()
+            .get_decoder()
+            .map(|d| Arc::new(d.clone()))
+    }
+
+    pub fn set_decoder(&self, decoder: Arc<RustDecoder>) {
+        self.tokenizer
+            .write()
+            .unwrap()
+            .with_decoder(decoder.as_ref().clone());
+    }
+}
+
+/// Returns a copy of the tokenizer which encodes the sequences as they are: without
+/// truncation, padding nor special tokens. The model and the pipeline are shared with the
+/// tokenizer, so it is cheap to make.
+fn unprocessed(tokenizer: &Tokenizer) -> Tokenizer {
+    let mut unprocessed = tokenizer.clone();
+    unprocessed
+        .with_padding(None)
+        .with_post_processor(RustPostProcessor::identity());
+    // Only the truncation parameters can be rejected
+    let _ = unprocessed.with_truncation(None);
+    unprocessed
+}
+
+/// Encodes a single sequence of an input with the `unprocessed` tokenizer.
+fn encode_sequence(
+    unprocessed: &Tokenizer,
+    sequence: InputSequence,
+    type_id: u32,
+    char_offsets: bool,
+) -> tk::Result<Encoding> {
+    let encoding = if char_offsets {
+        unprocessed.encode_char_offsets(sequence, false)?
+    } else {
+        unprocessed.encode(sequence, false)?
+    };
+
+    Ok(with_type_id(&encoding, type_id))
+}
+
+/// Encodes the input like upstream does, but checks its truncation before post-processing it,
+/// rather than panicking.
+fn encode_input(
+    tokenizer: &Tokenizer,
+    unprocessed: &Tokenizer,
+    input: EncodeInput,
+    add_special_tokens: bool,
+    char_offsets: bool,
+) -> Result<Encoding> {
+    let (sequence, pair) = match input {
+        EncodeInput::Single(sequence) => (sequence, None),
+        EncodeInput::Dual(sequence, pair) => (sequence, Some(pair)),
+    };
+
+    let encoding = encode_sequence(unprocessed, sequence, 0, char_offsets)?;
+    let pair_encoding = pair
+        .map(|pair| encode_sequence(unprocessed, pair, 1, char_offsets))
+        .transpose()?;
+
+    check_truncation(
+        tokenizer,
+        &encoding,
+        pair_encoding.as_ref(),
+        add_special_tokens,
+    )?;
+
+    Ok(tokenizer.post_process(encoding, pair_encoding, add_special_tokens)?)
+}
+
+/// Encodes the inputs in parallel, and pads them together.
+fn encode_inputs(
+    tokenizer: &Tokenizer,
+    inputs: Vec<RustEncodeInput>,
+    add_special_tokens: bool,
+    char_offsets: bool,
+) -> Result<Vec<Encoding>> {
+    let unprocessed = unprocessed(tokenizer);
+    let mut encodings = inputs
+        .into_maybe_par_iter()
+        .map(|input| {
+            encode_input(
+                tokenizer,
+                &unprocessed,
+                input.into(),
+                add_special_tokens,
+                char_offsets,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(padding) = tokenizer.get_padding() {
+        tk::pad_encodings(&mut encodings, padding)?;
+    }
+
+    Ok(encodings)
+}
+
+/// Upstream panics when `max_length` doesn't leave room for the special tokens, or when it
+/// truncates a sequence to no more than `stride` tokens. The sequences are truncated without
+/// stride beforehand, to check the lengths upstream truncates them to.
+fn check_truncation(
+    tokenizer: &Tokenizer,
+    encoding: &Encoding,
+    pair_encoding: Option<&Encoding>,
+    add_special_tokens: bool,
+) -> Result<()> {
+    let params = match tokenizer.get_truncation() {
+        Some(params) => params,
+        None => return Ok(()),
+    };
+
+    let n_added_tokens = match tokenizer.get_post_processor() {
+        Some(processor) if add_special_tokens => processor.added_tokens(pair_encoding.is_some()),
+        _ => 0,
+    };
+    let max_length = params
+        .max_length
+        .checked_sub(n_added_tokens)
+        .ok_or_else(|| {
+            TokenizersError::ValueError(format!(
+                "`max_length` must be at least the number of added special tokens ({}) but was {}",
+                n_added_tokens, params.max_length
+            ))
+        })?;
+
+    let total_length = encoding.len() + pair_encoding.map_or(0, Encoding::len);
+    if params.stride == 0 || total_length <= max_length {
+        return Ok(());
+    }
+
+    let without_stride = TruncationParams {
+        max_length,
+        stride: 0,
+        ..params.clone()
+    };
+    let (truncated, truncated_pair) =
+        tk::truncate_encodings(encoding.clone(), pair_encoding.cloned(), &without_stride)?;
+
+    let lengths = std::iter::once((truncated.len(), encoding.len())).chain(
+        truncated_pair
+            .iter()
+            .zip(pair_encoding)
+            .map(|(truncated, encoding)| (truncated.len(), encoding.len())),
+    );
+    for (i, (truncated_length, length)) in lengths.enumerate() {
+        if truncated_length < length && truncated_length > 0 && params.stride >= truncated_length {
+            return Err(TokenizersError::ValueError(format!(
+                "`stride` must be less than the truncated length of the sequence #{} ({}) but was {}",
+                i, truncated_length, params.stride
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Truncates the segments so that they fit in `max_length` along with the special tokens,
+/// like upstream does for pairs. `LongestFirst` shortens the longest segments first, while
+/// `OnlyFirst` and `OnlySecond` only truncate the segment A or B.
+///
+/// Upstream gives the token left over by `LongestFirst` to the longest sequence, or to the
+/// second one when they are as long, so the leftovers go to the longest segments and then to
+/// the last ones.
+fn truncate_segments(
+    encodings: &mut [Encoding],
+    n_special_tokens: usize,
+    params: &TruncationParams,
+) -> Result<()> {
+    let max_length = params.max_length.saturating_sub(n_special_tokens);
+    let lengths: Vec<usize> = encodings.iter().map(|encoding| encoding.len()).collect();
+    let total_length: usize = lengths.iter().sum();
+
+    if total_length <= max_length {
+        return Ok(());
+    }
+
+    let mut targets = lengths.clone();
+
+    match params.strategy {
+        TruncationStrategy::LongestFirst => {
+            // The largest length such that capping every segment to it fits, the remaining
+            // room going to some of the segments which were capped.
+            let capped_length = |cap: usize| lengths.iter().map(|&l| l.min(cap)).sum::<usize>();
+            let cap = (0..=max_length)
+                .rev()
+                .find(|&cap| capped_length(cap) <= max_length)
+                .unwrap_or(0);
+            let room = max_length - capped_length(cap);
+
+            let mut capped: Vec<usize> = (0..lengths.len()).filter(|&i| lengths[i] > cap).collect();
+            capped.sort_by_key(|&i| std::cmp::Reverse((lengths[i], i)));
+
+            for (target, &length) in targets.iter_mut().zip(&lengths) {
+                *target = length.min(cap);
+            }
+            for &i in capped.iter().take(room) {
+                targets[i] += 1;
+            }
+        }
+        TruncationStrategy::OnlyFirst | TruncationStrategy::OnlySecond => {
+            let index = if params.strategy == TruncationStrategy::OnlyFirst {
+                0
+            } else {
+                1
+            };
+
+            let length = *lengths.get(index).ok_or_else(|| {
+                TokenizersError::ValueError(
+                    "`OnlySecond` truncation needs at least two segments".into(),
+                )
+            })?;
+
+            let to_remove = total_length - max_length;
+            if length <= to_remove {
+                return Err(TokenizersError::ValueError(format!(
+                    "The segment #{} is too short to be truncated to `max_length`",
+                    index
+                )));
+            }
+
+            targets[index] = length - to_remove;
+        }
+    }
+
+    for (i, (encoding, target)) in encodings.iter_mut().zip(targets).enumerate() {
+        if target < encoding.len() {
+            if target > 0 && params.stride >= target {
+                return Err(TokenizersError::ValueError(format!(
+                    "`stride` must be less than the truncated length of the segment #{} ({}) but was {}",
+                    i, target, params.stride
+                )));
+            }
+
+            encoding.truncate(target, params.stride, params.direction);
+        }
+    }
+
+    Ok(())
+}
+
+//MARK: Decode Stream
+
+/// Decodes the ids generated by a model one at a time, e.g. to show a reply while it is
+/// being generated. Each step only decodes the ids since the previous text.
+pub struct RustDecodeStream {
+    tokenizer: Arc<RwLock<Tokenizer>>,
+    stream: Mutex<DecodeStream>,
+}
+
+impl RustDecodeStream {
+    /// Returns the text completed by the given id, or `None` while it is pending, e.g. when
+    /// it holds the first bytes of a character.
+    pub fn step(&self, id: u32) -> Result<Option<String>> {
+        let tokenizer = self.tokenizer.read().unwrap();
+        self.stream.lock().unwrap().step(&tokenizer, id)
+    }
+
+    /// Returns the text of the pending ids, if any, once the generation is over.
+    pub fn flush(&self) -> Result<Option<String>> {
+        let tokenizer = self.tokenizer.read().unwrap();
+        self.stream.lock().unwrap().flush(&tokenizer)
+    }
+}
+
+//MARK: Stop Sequences
+
+/// Where a stop string was found in the decoded output.
+#[derive(Clone)]
+pub struct RustStopMatch {
+    pub stop: String,
+    /// The char position of the stop string in the decoded output.
+    pub char_index: usize,
+    /// The index of the first id decoding to the stop string, among the ones given to the
+    /// matcher. This id may also decode to some text before the stop string.
+    pub token_index: usize,
+}
+
+/// Follows the ids generated by a model, and reports the first stop string appearing in the
+/// decoded output, even when it spans several tokens.
+pub struct RustStopSequenceMatcher {
+    tokenizer: Arc<RwLock<Tokenizer>>,
+    state: Mutex<StopSequenceState>,
+}
+
+struct StopSequenceState {
+    stops: Vec<String>,
+    stream: DecodeStream,
+    text: String,
+    /// The byte end of each fragment of `text`, and the index of its first id.
+    fragments: Vec<(usize, usize)>,
+    n_ids: usize,
+    /// The index of the first id which isn't part of a fragment yet.
+    first_pending: usize,
+    found: Option<RustStopMatch>,
+}
+
+impl RustStopSequenceMatcher {
+    /// Returns the stop string completed by the given id, if any. Once a stop string is
+    /// found, the next ids are ignored and the same match is returned.
+    pub fn step(&self, id: u32) -> Result<Option<RustStopMatch>> {
+        let tokenizer = self.tokenizer.read().unwrap();
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+
+        if state.found.is_some() {
+            return Ok(state.found.clone());
+        }
+
+        let index = state.n_ids;
+        state.n_ids += 1;
+
+        let fragment = match state.stream.step(&tokenizer, id)? {
+            Some(fragment) => fragment,
+            None => return Ok(None),
+        };
+
+        // A stop string may start in the previous text, as long as it ends in the new one
+        let max_stop_len = state.stops.iter().map(|stop| stop.len()).max().unwrap_or(0);
+        let mut search_start = state.text.len().saturating_sub(max_stop_len - 1);
+        while !state.text.is_char_boundary(search_start) {
+            search_start -= 1;
+        }
+
+        state.text.push_str(&fragment);
+        state
+            .fragments
+            .push((state.text.len(), state.first_pending));
+        state.first_pending = index + 1;
+
+        let text = &state.text;
+        let found = state
+            .stops
+            .iter()
+            .filter_map(|stop| {
+                text[search_start..]
+                    .find(stop.as_str())
+                    .map(|position| (search_start + position, stop))
+            })
+            .min_by_key(|(position, _)| *position);
+
+        if let Some((position, stop)) = found {
+            let fragment = state.fragments.partition_point(|(end, _)| *end <= position);
+
+            state.found = Some(RustStopMatch {
+                stop: stop.clone(),
+                char_index: text[..position].chars().count(),
+                token_index: state.fragments[fragment].1,
+            });
+        }
+
+        Ok(state.found.clone())
+    }
 }
 
 //MARK: Encoding
@@ -186,6 +1128,99 @@ impl RustEncoding {
     pub fn get_attention_mask(&self) -> Vec<u32> {
         self.encoding.get_attention_mask().to_vec()
     }
+
+    pub fn get_offsets(&self) -> Vec<RustOffsets> {
+        self.encoding.get_offsets().to_vec()
+    }
+
+    /// The index of the word each token comes from, or `None` for the special tokens.
+    pub fn get_word_ids(&self) -> Vec<Option<u32>> {
+        self.encoding.get_word_ids().to_vec()
+    }
+
+    /// The index of the sequence each token comes from, or `None` for the special tokens.
+    pub fn get_sequence_ids(&self) -> Vec<Option<usize>> {
+        self.encoding.get_sequence_ids()
+    }
+
+    pub fn get_special_tokens_mask(&self) -> Vec<u32> {
+        self.encoding.get_special_tokens_mask().to_vec()
+    }
+
+    pub fn get_n_sequences(&self) -> usize {
+        self.encoding.n_sequences()
+    }
+
+    /// The index of the sequence containing the given token.
+    pub fn token_to_sequence(&self, token_index: usize) -> Option<usize> {
+        self.encoding.token_to_sequence(token_index)
+    }
+
+    /// The offsets of the given token in its input sequence. If `sequence_index` is given,
+    /// the token must belong to this sequence.
+    pub fn token_to_chars(
+        &self,
+        token_index: usize,
+        sequence_index: Option<usize>,
+    ) -> Option<RustOffsets> {
+        self.encoding
+            .token_to_chars(token_index)
+            .filter(|(sequence, _)| sequence_index.map_or(true, |index| index == *sequence))
+            .map(|(_, offsets)| offsets)
+    }
+
+    /// The index of the word containing the given token in its input sequence. If
+    /// `sequence_index` is given, the token must belong to this sequence.
+    pub fn token_to_word(&self, token_index: usize, sequence_index: Option<usize>) -> Option<u32> {
+        self.encoding
+            .token_to_word(token_index)
+            .filter(|(sequence, _)| sequence_index.map_or(true, |index| index == *sequence))
+            .map(|(_, word)| word)
+    }
+
+    /// The index of the token containing the given char of the input sequence, which is the
+    /// first one by default.
+    pub fn char_to_token(&self, char_pos: usize, sequence_index: Option<usize>) -> Option<usize> {
+        self.encoding
+            .char_to_token(char_pos, sequence_index.unwrap_or(0))
+    }
+
+    /// The index of the word containing the given char of the input sequence, which is the
+    /// first one by default.
+    pub fn char_to_word(&self, char_pos: usize, sequence_index: Option<usize>) -> Option<u32> {
+        self.encoding
+            .char_to_word(char_pos, sequence_index.unwrap_or(0))
+    }
+
+    /// The range of tokens of the given word of the input sequence, which is the first one
+    /// by default.
+    pub fn word_to_tokens(
+        &self,
+        word_index: u32,
+        sequence_index: Option<usize>,
+    ) -> Option<RustOffsets> {
+        self.encoding
+            .word_to_tokens(word_index, sequence_index.unwrap_or(0))
+    }
+
+    /// The offsets of the given word of the input sequence, which is the first one by default.
+    pub fn word_to_chars(
+        &self,
+        word_index: u32,
+        sequence_index: Option<usize>,
+    ) -> Option<RustOffsets> {
+        self.encoding
+            .word_to_chars(word_index, sequence_index.unwrap_or(0))
+    }
+
+    /// The encodings of the tokens which didn't fit when truncating, if any.
+    pub fn get_overflowing(&self) -> Vec<Arc<RustEncoding>> {
+        self.encoding
+            .get_overflowing()
+            .iter()
+            .map(|encoding| Arc::new(RustEncoding::new(Arc::new(encoding.clone()))))
+            .collect()
+    }
 }
 
 //MARK: Added Tokens
@@ -1,44 +1,52 @@
-use crate::{error::Result, RustAddedToken, RustBpe};
+use crate::error::{Result, TokenizersError};
+use crate::utils::{serialized_field, single_char};
+use crate::{RustAddedToken, RustModel};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::{Arc, RwLock};
 use tk::{
-    models::{bpe::BpeTrainer, TrainerWrapper},
+    models::{
+        bpe::BpeTrainer, unigram::UnigramTrainer, wordlevel::WordLevelTrainer,
+        wordpiece::WordPieceTrainer, TrainerWrapper,
+    },
     Trainer,
 };
 use tokenizers as tk;
 
+/// Base class for all trainers
+///
+/// This class is not supposed to be instantiated directly. Instead, any implementation of a
+/// Trainer will return an instance of this class when converted with `as_trainer`.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct RustBpeTrainer {
-    trainer: Arc<RwLock<BpeTrainer>>,
+pub struct RustTrainer {
+    #[serde(flatten)]
+    pub(crate) trainer: Arc<RwLock<TrainerWrapper>>,
 }
 
-impl From<BpeTrainer> for RustBpeTrainer {
-    fn from(trainer: BpeTrainer) -> Self {
+impl From<TrainerWrapper> for RustTrainer {
+    fn from(trainer: TrainerWrapper) -> Self {
         Self {
             trainer: Arc::new(RwLock::new(trainer)),
         }
     }
 }
 
-impl From<TrainerWrapper> for RustBpeTrainer {
-    fn from(trainer: TrainerWrapper) -> Self {
-        if let TrainerWrapper::BpeTrainer(bpe_trainer) = trainer {
-            bpe_trainer.into()
-        } else {
-            panic!("trainer must be BpeTrainer")
-        }
-    }
-}
-
-impl Trainer for RustBpeTrainer {
-    type Model = RustBpe;
+impl Trainer for RustTrainer {
+    type Model = RustModel;
 
     fn should_show_progress(&self) -> bool {
         self.trainer.read().unwrap().should_show_progress()
     }
 
     fn train(&self, model: &mut Self::Model) -> tk::Result<Vec<tk::AddedToken>> {
-        model.with_subtype_mut(|bpe| self.trainer.read().unwrap().train(bpe))
+        let special_tokens = self
+            .trainer
+            .read()
+            .unwrap()
+            .train(&mut model.model.write().unwrap());
+        // The model was replaced in place
+        model.unigram_index.clear();
+        special_tokens
     }
 
     fn feed<I, S, F>(&mut self, iterator: I, process: F) -> tk::Result<()>
@@ -51,7 +59,85 @@ impl Trainer for RustBpeTrainer {
     }
 }
 
+impl RustTrainer {
+    /// Returns the concrete BpeTrainer handle sharing this trainer, if any.
+    pub fn as_bpe_trainer(&self) -> Option<Arc<RustBpeTrainer>> {
+        match *self.trainer.read().unwrap() {
+            TrainerWrapper::BpeTrainer(_) => Some(Arc::new(RustBpeTrainer {
+                trainer: self.trainer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete WordPieceTrainer handle sharing this trainer, if any.
+    pub fn as_wordpiece_trainer(&self) -> Option<Arc<RustWordPieceTrainer>> {
+        match *self.trainer.read().unwrap() {
+            TrainerWrapper::WordPieceTrainer(_) => Some(Arc::new(RustWordPieceTrainer {
+                trainer: self.trainer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete WordLevelTrainer handle sharing this trainer, if any.
+    pub fn as_wordlevel_trainer(&self) -> Option<Arc<RustWordLevelTrainer>> {
+        match *self.trainer.read().unwrap() {
+            TrainerWrapper::WordLevelTrainer(_) => Some(Arc::new(RustWordLevelTrainer {
+                trainer: self.trainer.clone(),
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the concrete UnigramTrainer handle sharing this trainer, if any.
+    pub fn as_unigram_trainer(&self) -> Option<Arc<RustUnigramTrainer>> {
+        match *self.trainer.read().unwrap() {
+            TrainerWrapper::UnigramTrainer(_) => Some(Arc::new(RustUnigramTrainer {
+                trainer: self.trainer.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+fn special_tokens_of(special_tokens: &[tk::AddedToken]) -> Vec<Arc<RustAddedToken>> {
+    special_tokens
+        .iter()
+        .map(|t| Arc::new(t.clone().into()))
+        .collect()
+}
+
+/// Each string of the alphabet must be a single character.
+fn alphabet_of(initial_alphabet: Vec<String>) -> Result<HashSet<char>> {
+    initial_alphabet
+        .iter()
+        .map(|s| single_char(s, "initial_alphabet"))
+        .collect()
+}
+
+/// Trainer capable of training a BPE model
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustBpeTrainer {
+    #[serde(flatten)]
+    trainer: Arc<RwLock<TrainerWrapper>>,
+}
+
+impl RustBpeTrainer {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&BpeTrainer) -> R,
+    {
+        if let TrainerWrapper::BpeTrainer(trainer) = &*self.trainer.read().unwrap() {
+            callback(trainer)
+        } else {
+            unreachable!("the trainer must be a `BpeTrainer`")
+        }
+    }
+}
+
 impl RustBpeTrainer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         vocab_size: Option<usize>,
         min_frequency: Option<u32>,
@@ -81,12 +167,7 @@ impl RustBpeTrainer {
             builder = builder.limit_alphabet(limit_alphabet);
         }
         if let Some(initial_alphabet) = initial_alphabet {
-            builder = builder.initial_alphabet(
-                initial_alphabet
-                    .into_iter()
-                    .filter_map(|s| s.chars().next())
-                    .collect(),
-            );
+            builder = builder.initial_alphabet(alphabet_of(initial_alphabet)?);
         }
         if let Some(continuing_subword_prefix) = continuing_subword_prefix {
             builder = builder.continuing_subword_prefix(continuing_subword_prefix);
@@ -96,17 +177,285 @@ impl RustBpeTrainer {
         }
 
         Ok(Self {
-            trainer: Arc::new(RwLock::new(builder.build())),
+            trainer: Arc::new(RwLock::new(builder.build().into())),
         })
     }
 
     pub fn get_special_tokens(&self) -> Vec<Arc<RustAddedToken>> {
-        self.trainer
-            .read()
-            .unwrap()
-            .special_tokens
-            .iter()
-            .map(|t| Arc::new(t.clone().into()))
-            .collect()
+        self.with_subtype(|trainer| special_tokens_of(&trainer.special_tokens))
+    }
+
+    /// Returns a trainer handle sharing this BpeTrainer, to be given to `RustTokenizer::train`.
+    pub fn as_trainer(&self) -> Arc<RustTrainer> {
+        Arc::new(RustTrainer {
+            trainer: self.trainer.clone(),
+        })
+    }
+}
+
+/// Trainer capable of training a WordLevel model
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustWordLevelTrainer {
+    #[serde(flatten)]
+    trainer: Arc<RwLock<TrainerWrapper>>,
+}
+
+impl RustWordLevelTrainer {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&WordLevelTrainer) -> R,
+    {
+        if let TrainerWrapper::WordLevelTrainer(trainer) = &*self.trainer.read().unwrap() {
+            callback(trainer)
+        } else {
+            unreachable!("the trainer must be a `WordLevelTrainer`")
+        }
+    }
+}
+
+impl RustWordLevelTrainer {
+    pub fn new(
+        vocab_size: Option<usize>,
+        min_frequency: Option<u32>,
+        show_progress: Option<bool>,
+        special_tokens: Option<Vec<Arc<RustAddedToken>>>,
+    ) -> Result<Self> {
+        let mut builder = WordLevelTrainer::builder();
+
+        if let Some(vocab_size) = vocab_size {
+            builder.vocab_size(vocab_size);
+        }
+        if let Some(min_frequency) = min_frequency {
+            builder.min_frequency(min_frequency);
+        }
+        if let Some(show_progress) = show_progress {
+            builder.show_progress(show_progress);
+        }
+        if let Some(special_tokens) = special_tokens {
+            builder.special_tokens(special_tokens.iter().map(|t| t.as_ref().into()).collect());
+        }
+
+        let trainer = builder.build().map_err(|e| {
+            TokenizersError::Exception(format!("Error while initializing WordLevelTrainer: {}", e))
+        })?;
+
+        Ok(Self {
+            trainer: Arc::new(RwLock::new(trainer.into())),
+        })
+    }
+
+    pub fn get_vocab_size(&self) -> usize {
+        self.with_subtype(|trainer| trainer.vocab_size)
+    }
+
+    pub fn get_min_frequency(&self) -> u32 {
+        self.with_subtype(|trainer| trainer.min_frequency)
+    }
+
+    pub fn get_special_tokens(&self) -> Vec<Arc<RustAddedToken>> {
+        self.with_subtype(|trainer| special_tokens_of(&trainer.special_tokens))
+    }
+
+    /// Returns a trainer handle sharing this WordLevelTrainer, to be given to
+    /// `RustTokenizer::train`.
+    pub fn as_trainer(&self) -> Arc<RustTrainer> {
+        Arc::new(RustTrainer {
+            trainer: self.trainer.clone(),
+        })
+    }
+}
+
+/// Trainer capable of training a WordPiece model
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustWordPieceTrainer {
+    #[serde(flatten)]
+    trainer: Arc<RwLock<TrainerWrapper>>,
+}
+
+impl RustWordPieceTrainer {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&WordPieceTrainer) -> R,
+    {
+        if let TrainerWrapper::WordPieceTrainer(trainer) = &*self.trainer.read().unwrap() {
+            callback(trainer)
+        } else {
+            unreachable!("the trainer must be a `WordPieceTrainer`")
+        }
+    }
+}
+
+impl RustWordPieceTrainer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        vocab_size: Option<usize>,
+        min_frequency: Option<u32>,
+        show_progress: Option<bool>,
+        special_tokens: Option<Vec<Arc<RustAddedToken>>>,
+        limit_alphabet: Option<usize>,
+        initial_alphabet: Option<Vec<String>>,
+        continuing_subword_prefix: Option<String>,
+        end_of_word_suffix: Option<String>,
+    ) -> Result<Self> {
+        let mut builder = WordPieceTrainer::builder();
+
+        if let Some(vocab_size) = vocab_size {
+            builder = builder.vocab_size(vocab_size);
+        }
+        if let Some(min_frequency) = min_frequency {
+            builder = builder.min_frequency(min_frequency);
+        }
+        if let Some(show_progress) = show_progress {
+            builder = builder.show_progress(show_progress);
+        }
+        if let Some(special_tokens) = special_tokens {
+            let special_tokens = special_tokens.iter().map(|t| t.as_ref().into()).collect();
+            builder = builder.special_tokens(special_tokens);
+        }
+        if let Some(limit_alphabet) = limit_alphabet {
+            builder = builder.limit_alphabet(limit_alphabet);
+        }
+        if let Some(initial_alphabet) = initial_alphabet {
+            builder = builder.initial_alphabet(alphabet_of(initial_alphabet)?);
+        }
+        if let Some(continuing_subword_prefix) = continuing_subword_prefix {
+            builder = builder.continuing_subword_prefix(continuing_subword_prefix);
+        }
+        if let Some(end_of_word_suffix) = end_of_word_suffix {
+            builder = builder.end_of_word_suffix(end_of_word_suffix);
+        }
+
+        Ok(Self {
+            trainer: Arc::new(RwLock::new(builder.build().into())),
+        })
+    }
+
+    pub fn get_vocab_size(&self) -> usize {
+        self.with_subtype(|trainer| trainer.vocab_size())
+    }
+
+    pub fn get_min_frequency(&self) -> u32 {
+        self.with_subtype(|trainer| trainer.min_frequency())
+    }
+
+    pub fn get_special_tokens(&self) -> Vec<Arc<RustAddedToken>> {
+        self.with_subtype(|trainer| special_tokens_of(trainer.special_tokens()))
+    }
+
+    /// Returns a trainer handle sharing this WordPieceTrainer, to be given to
+    /// `RustTokenizer::train`.
+    pub fn as_trainer(&self) -> Arc<RustTrainer> {
+        Arc::new(RustTrainer {
+            trainer: self.trainer.clone(),
+        })
+    }
+}
+
+/// Trainer capable of training a Unigram model
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustUnigramTrainer {
+    #[serde(flatten)]
+    trainer: Arc<RwLock<TrainerWrapper>>,
+}
+
+impl RustUnigramTrainer {
+    fn with_subtype<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&UnigramTrainer) -> R,
+    {
+        if let TrainerWrapper::UnigramTrainer(trainer) = &*self.trainer.read().unwrap() {
+            callback(trainer)
+        } else {
+            unreachable!("the trainer must be a `UnigramTrainer`")
+        }
+    }
+}
+
+impl RustUnigramTrainer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        vocab_size: Option<u32>,
+        show_progress: Option<bool>,
+        special_tokens: Option<Vec<Arc<RustAddedToken>>>,
+        initial_alphabet: Option<Vec<String>>,
+        shrinking_factor: Option<f64>,
+        unk_token: Option<String>,
+        max_piece_length: Option<usize>,
+        n_sub_iterations: Option<u32>,
+        seed_size: Option<usize>,
+    ) -> Result<Self> {
+        let mut builder = UnigramTrainer::builder();
+
+        if let Some(vocab_size) = vocab_size {
+            builder.vocab_size(vocab_size);
+        }
+        if let Some(show_progress) = show_progress {
+            builder.show_progress(show_progress);
+        }
+        if let Some(special_tokens) = special_tokens {
+            builder.special_tokens(special_tokens.iter().map(|t| t.as_ref().into()).collect());
+        }
+        if let Some(initial_alphabet) = initial_alphabet {
+            builder.initial_alphabet(alphabet_of(initial_alphabet)?);
+        }
+        if let Some(shrinking_factor) = shrinking_factor {
+            builder.shrinking_factor(shrinking_factor);
+        }
+        if let Some(unk_token) = unk_token {
+            builder.unk_token(Some(unk_token));
+        }
+        if let Some(max_piece_length) = max_piece_length {
+            builder.max_piece_length(max_piece_length);
+        }
+        if let Some(n_sub_iterations) = n_sub_iterations {
+            builder.n_sub_iterations(n_sub_iterations);
+        }
+        if let Some(seed_size) = seed_size {
+            builder.seed_size(seed_size);
+        }
+
+        let trainer = builder.build().map_err(|e| {
+            TokenizersError::Exception(format!("Error while initializing UnigramTrainer: {}", e))
+        })?;
+
+        Ok(Self {
+            trainer: Arc::new(RwLock::new(trainer.into())),
+        })
+    }
+
+    pub fn get_vocab_size(&self) -> u32 {
+        self.with_subtype(|trainer| trainer.vocab_size)
+    }
+
+    pub fn get_shrinking_factor(&self) -> f64 {
+        self.with_subtype(|trainer| trainer.shrinking_factor)
+    }
+
+    pub fn get_unk_token(&self) -> Option<String> {
+        self.with_subtype(|trainer| trainer.unk_token.clone())
+    }
+
+    pub fn get_max_piece_length(&self) -> usize {
+        self.with_subtype(|trainer| trainer.max_piece_length)
+    }
+
+    pub fn get_n_sub_iterations(&self) -> u32 {
+        self.with_subtype(|trainer| trainer.n_sub_iterations)
+    }
+
+    pub fn get_seed_size(&self) -> usize {
+        self.with_subtype(|trainer| serialized_field(trainer, "seed_size"))
+    }
+
+    pub fn get_special_tokens(&self) -> Vec<Arc<RustAddedToken>> {
+        self.with_subtype(|trainer| special_tokens_of(&trainer.special_tokens))
+    }
+
+    /// Returns a trainer handle sharing this UnigramTrainer, to be given to
+    /// `RustTokenizer::train`.
+    pub fn as_trainer(&self) -> Arc<RustTrainer> {
+        Arc::new(RustTrainer {
+            trainer: self.trainer.clone(),
+        })
     }
 }
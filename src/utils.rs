@@ -1,98 +1,283 @@
-use std::collections::HashMap;
-
-use crate::error::TokenizersError;
-use crate::UniffiCustomTypeConverter;
+use crate::error::{Result, TokenizersError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 pub use tk::models::bpe::{Merges as RustMerges, Vocab as RustVocab};
+use tk::normalizers::replace::ReplacePattern;
+use tk::pre_tokenizers::split::SplitPattern;
 pub use tk::Offsets as RustOffsets;
+use tk::{
+    PaddingDirection, PaddingParams, PaddingStrategy, SplitDelimiterBehavior, TruncationDirection,
+    TruncationParams, TruncationStrategy,
+};
 use tokenizers as tk;
 
 pub type RustUSize = usize;
 
-impl UniffiCustomTypeConverter for RustUSize {
-    type Builtin = u64;
+/// Upstream doesn't expose some of the fields of its components, but they are part of
+/// their serialized form.
+pub(crate) fn serialized_field<T, R>(component: &T, field: &str) -> R
+where
+    T: Serialize,
+    R: DeserializeOwned,
+{
+    serde_json::to_value(component)
+        .ok()
+        .and_then(|mut value| serde_json::from_value(value[field].take()).ok())
+        .expect("field must be serialized")
+}
+
+/// Converts the given string into a single `char`, failing if it isn't exactly one character long.
+pub(crate) fn single_char(value: &str, name: &str) -> Result<char> {
+    let mut chars = value.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(TokenizersError::ValueError(format!(
+            "`{}` must be a single character but was {:?}",
+            name, value
+        ))),
+    }
+}
+
+/// A pattern used to match parts of a string, either literally or with a regular expression.
+pub enum RustPattern {
+    String { value: String },
+    Regex { pattern: String },
+}
+
+impl From<RustPattern> for ReplacePattern {
+    fn from(pattern: RustPattern) -> Self {
+        match pattern {
+            RustPattern::String { value } => Self::String(value),
+            RustPattern::Regex { pattern } => Self::Regex(pattern),
+        }
+    }
+}
+
+impl From<RustPattern> for SplitPattern {
+    fn from(pattern: RustPattern) -> Self {
+        match pattern {
+            RustPattern::String { value } => Self::String(value),
+            RustPattern::Regex { pattern } => Self::Regex(pattern),
+        }
+    }
+}
+
+impl From<SplitPattern> for RustPattern {
+    fn from(pattern: SplitPattern) -> Self {
+        match pattern {
+            SplitPattern::String(value) => Self::String { value },
+            SplitPattern::Regex(pattern) => Self::Regex { pattern },
+        }
+    }
+}
+
+/// The behavior to adopt when splitting a string on a delimiter.
+///
+/// With the delimiter `-` and the input `the-final--countdown`:
+///  - Removed => `[ "the", "final", "countdown" ]`
+///  - Isolated => `[ "the", "-", "final", "-", "-", "countdown" ]`
+///  - MergedWithPrevious => `[ "the-", "final-", "-", "countdown" ]`
+///  - MergedWithNext => `[ "the", "-final", "-", "-countdown" ]`
+///  - Contiguous => `[ "the", "-", "final", "--", "countdown" ]`
+pub enum RustSplitDelimiterBehavior {
+    Removed,
+    Isolated,
+    MergedWithPrevious,
+    MergedWithNext,
+    Contiguous,
+}
 
-    fn into_custom(value: Self::Builtin) -> uniffi::Result<Self>
-    where
-        Self: Sized,
-    {
-        Ok(usize::try_from(value)?)
+impl From<RustSplitDelimiterBehavior> for SplitDelimiterBehavior {
+    fn from(behavior: RustSplitDelimiterBehavior) -> Self {
+        match behavior {
+            RustSplitDelimiterBehavior::Removed => Self::Removed,
+            RustSplitDelimiterBehavior::Isolated => Self::Isolated,
+            RustSplitDelimiterBehavior::MergedWithPrevious => Self::MergedWithPrevious,
+            RustSplitDelimiterBehavior::MergedWithNext => Self::MergedWithNext,
+            RustSplitDelimiterBehavior::Contiguous => Self::Contiguous,
+        }
     }
+}
+
+impl From<SplitDelimiterBehavior> for RustSplitDelimiterBehavior {
+    fn from(behavior: SplitDelimiterBehavior) -> Self {
+        match behavior {
+            SplitDelimiterBehavior::Removed => Self::Removed,
+            SplitDelimiterBehavior::Isolated => Self::Isolated,
+            SplitDelimiterBehavior::MergedWithPrevious => Self::MergedWithPrevious,
+            SplitDelimiterBehavior::MergedWithNext => Self::MergedWithNext,
+            SplitDelimiterBehavior::Contiguous => Self::Contiguous,
+        }
+    }
+}
+
+/// The side on which the padding is added.
+pub enum RustPaddingDirection {
+    Left,
+    Right,
+}
 
-    fn from_custom(value: Self) -> Self::Builtin {
-        value as u64
+impl From<RustPaddingDirection> for PaddingDirection {
+    fn from(direction: RustPaddingDirection) -> Self {
+        match direction {
+            RustPaddingDirection::Left => Self::Left,
+            RustPaddingDirection::Right => Self::Right,
+        }
     }
 }
 
-impl UniffiCustomTypeConverter for RustMerges {
-    type Builtin = Vec<Vec<String>>;
+impl From<PaddingDirection> for RustPaddingDirection {
+    fn from(direction: PaddingDirection) -> Self {
+        match direction {
+            PaddingDirection::Left => Self::Left,
+            PaddingDirection::Right => Self::Right,
+        }
+    }
+}
 
-    fn into_custom(v_merges: Self::Builtin) -> uniffi::Result<Self>
-    where
-        Self: Sized,
-    {
-        let mut merges: tk::models::bpe::Merges = vec![];
+/// The length to pad to: either the longest sequence of the batch, or a fixed size.
+pub enum RustPaddingStrategy {
+    BatchLongest,
+    Fixed { size: usize },
+}
 
-        for (i, m) in v_merges.iter().enumerate() {
-            if m.len() != 2 {
-                return Err(TokenizersError::ValueError(format!(
-                    "The element #{} in `merges` must be a list containing 2 elements but was {}",
-                    i,
-                    m.len()
-                ))
-                .into());
-            }
+impl From<RustPaddingStrategy> for PaddingStrategy {
+    fn from(strategy: RustPaddingStrategy) -> Self {
+        match strategy {
+            RustPaddingStrategy::BatchLongest => Self::BatchLongest,
+            RustPaddingStrategy::Fixed { size } => Self::Fixed(size),
+        }
+    }
+}
 
-            merges.push((m[0].clone(), m[1].clone()));
+impl From<PaddingStrategy> for RustPaddingStrategy {
+    fn from(strategy: PaddingStrategy) -> Self {
+        match strategy {
+            PaddingStrategy::BatchLongest => Self::BatchLongest,
+            PaddingStrategy::Fixed(size) => Self::Fixed { size },
         }
+    }
+}
+
+pub struct RustPaddingParams {
+    pub strategy: RustPaddingStrategy,
+    pub direction: RustPaddingDirection,
+    /// If set, the padded length is rounded up to a multiple of this value.
+    pub pad_to_multiple_of: Option<usize>,
+    pub pad_id: u32,
+    pub pad_type_id: u32,
+    pub pad_token: String,
+}
 
-        Ok(merges)
+impl From<RustPaddingParams> for PaddingParams {
+    fn from(params: RustPaddingParams) -> Self {
+        Self {
+            strategy: params.strategy.into(),
+            direction: params.direction.into(),
+            pad_to_multiple_of: params.pad_to_multiple_of,
+            pad_id: params.pad_id,
+            pad_type_id: params.pad_type_id,
+            pad_token: params.pad_token,
+        }
     }
+}
 
-    fn from_custom(obj: Self) -> Self::Builtin {
-        obj.iter().map(|m| vec![m.0.clone(), m.1.clone()]).collect()
+impl From<PaddingParams> for RustPaddingParams {
+    fn from(params: PaddingParams) -> Self {
+        Self {
+            strategy: params.strategy.into(),
+            direction: params.direction.into(),
+            pad_to_multiple_of: params.pad_to_multiple_of,
+            pad_id: params.pad_id,
+            pad_type_id: params.pad_type_id,
+            pad_token: params.pad_token,
+        }
     }
 }
 
-// For type alias, we need to a custom converter without any conversion.
-impl UniffiCustomTypeConverter for RustVocab {
-    type Builtin = HashMap<String, u32>;
+/// The side from which the tokens are removed when truncating.
+pub enum RustTruncationDirection {
+    Left,
+    Right,
+}
 
-    fn into_custom(value: Self::Builtin) -> uniffi::Result<Self>
-    where
-        Self: Sized,
-    {
-        Ok(value)
+impl From<RustTruncationDirection> for TruncationDirection {
+    fn from(direction: RustTruncationDirection) -> Self {
+        match direction {
+            RustTruncationDirection::Left => Self::Left,
+            RustTruncationDirection::Right => Self::Right,
+        }
     }
+}
 
-    fn from_custom(value: Self) -> Self::Builtin {
-        value
+impl From<TruncationDirection> for RustTruncationDirection {
+    fn from(direction: TruncationDirection) -> Self {
+        match direction {
+            TruncationDirection::Left => Self::Left,
+            TruncationDirection::Right => Self::Right,
+        }
     }
 }
 
-impl UniffiCustomTypeConverter for RustOffsets {
-    type Builtin = Vec<u64>;
+/// The sequences to truncate when encoding a pair.
+///
+///  - LongestFirst => Truncates the longest sequence first, until both fit
+///  - OnlyFirst => Only truncates the first sequence
+///  - OnlySecond => Only truncates the second sequence
+pub enum RustTruncationStrategy {
+    LongestFirst,
+    OnlyFirst,
+    OnlySecond,
+}
 
-    fn into_custom(value: Self::Builtin) -> uniffi::Result<Self>
-    where
-        Self: Sized,
-    {
-        if value.len() != 2 {
-            return Err(TokenizersError::ValueError(format!(
-                "The length of value must be 2 but was {}",
-                value.len()
-            ))
-            .into());
+impl From<RustTruncationStrategy> for TruncationStrategy {
+    fn from(strategy: RustTruncationStrategy) -> Self {
+        match strategy {
+            RustTruncationStrategy::LongestFirst => Self::LongestFirst,
+            RustTruncationStrategy::OnlyFirst => Self::OnlyFirst,
+            RustTruncationStrategy::OnlySecond => Self::OnlySecond,
         }
+    }
+}
 
-        let start = usize::try_from(value[0])
-            .map_err(|e| TokenizersError::ValueError(format!("start offset: {}", e)))?;
-        let end = usize::try_from(value[1])
-            .map_err(|e| TokenizersError::ValueError(format!("end offset: {}", e)))?;
+impl From<TruncationStrategy> for RustTruncationStrategy {
+    fn from(strategy: TruncationStrategy) -> Self {
+        match strategy {
+            TruncationStrategy::LongestFirst => Self::LongestFirst,
+            TruncationStrategy::OnlyFirst => Self::OnlyFirst,
+            TruncationStrategy::OnlySecond => Self::OnlySecond,
+        }
+    }
+}
+
+pub struct RustTruncationParams {
+    /// The maximum length of the encodings, special tokens included.
+    pub max_length: usize,
+    /// The number of tokens the overflowing windows share with the previous one.
+    pub stride: usize,
+    pub strategy: RustTruncationStrategy,
+    pub direction: RustTruncationDirection,
+}
 
-        Ok((start, end))
+impl From<RustTruncationParams> for TruncationParams {
+    fn from(params: RustTruncationParams) -> Self {
+        Self {
+            max_length: params.max_length,
+            stride: params.stride,
+            strategy: params.strategy.into(),
+            direction: params.direction.into(),
+        }
     }
+}
 
-    fn from_custom(obj: Self) -> Self::Builtin {
-        vec![obj.0 as u64, obj.1 as u64]
+impl From<TruncationParams> for RustTruncationParams {
+    fn from(params: TruncationParams) -> Self {
+        Self {
+            max_length: params.max_length,
+            stride: params.stride,
+            strategy: params.strategy.into(),
+            direction: params.direction.into(),
+        }
     }
 }